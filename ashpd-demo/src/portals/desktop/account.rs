@@ -63,12 +63,12 @@ impl AccountPage {
         match request.send().await.and_then(|r| r.response()) {
             Ok(user_info) => {
                 self.success("User information request was successful");
-                imp.id_label.set_text(user_info.id());
-                imp.name_label.set_text(user_info.name());
+                imp.id_label.set_text(user_info.id().unwrap_or_default());
+                imp.name_label.set_text(user_info.name().unwrap_or_default());
                 match user_info
                     .image()
-                    .to_file_path()
-                    .map_err(|_| {
+                    .and_then(|image| image.to_file_path().ok())
+                    .ok_or_else(|| {
                         glib::Error::new(glib::FileError::Failed, "Failed to retrieve file path")
                     })
                     .and_then(gdk::Texture::from_filename)
@@ -60,7 +60,7 @@ impl OpenUriPage {
             Ok(uri) => {
                 let request = open_uri::OpenFileRequest::default()
                     .ask(ask)
-                    .writeable(writeable)
+                    .writable(writeable)
                     .identifier(identifier);
                 let response = if uri.scheme() == "file" {
                     let file_path = uri.to_file_path().unwrap();
@@ -0,0 +1,203 @@
+#![deny(missing_docs)]
+//! Derive macros of [ashpd](https://docs.rs/ashpd), use them through the
+//! re-exports of the `ashpd` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, ExprPath, Fields,
+    GenericArgument, LitStr, PathArguments, Token, Type,
+};
+
+/// Decodes the `a{sv}` results of a portal response into a struct.
+///
+/// See the documentation of `ashpd::desktop::ResponseResults`.
+#[proc_macro_derive(ResponseResults, attributes(response))]
+pub fn derive_response_results(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// What to do when the key of a field is missing from the results.
+enum Missing {
+    /// Fail with a missing field error.
+    Error,
+    /// Leave the field to `None`.
+    None,
+    /// Use `Default::default()`.
+    Default,
+    /// Call the given function.
+    With(ExprPath),
+}
+
+struct Field {
+    ident: syn::Ident,
+    key: String,
+    /// The type the value is decoded into, the inner type of the `Option`
+    /// fields.
+    ty: Type,
+    missing: Missing,
+}
+
+impl Field {
+    fn parse(field: &syn::Field) -> Result<Self, Error> {
+        let ident = field
+            .ident
+            .clone()
+            .ok_or_else(|| Error::new(field.span(), "only named fields are supported"))?;
+        let mut key = ident.to_string();
+        let mut missing = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("response")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    key = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    missing = Some(if meta.input.peek(Token![=]) {
+                        Missing::With(meta.value()?.parse::<LitStr>()?.parse()?)
+                    } else {
+                        Missing::Default
+                    });
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename` or `default`"))
+                }
+            })?;
+        }
+        let (ty, missing) = match (option_inner(&field.ty), missing) {
+            (Some(inner), None) => (inner.clone(), Missing::None),
+            (Some(_), Some(_)) => {
+                return Err(Error::new(
+                    field.span(),
+                    "`Option` fields already default to `None`",
+                ))
+            }
+            (None, missing) => (field.ty.clone(), missing.unwrap_or(Missing::Error)),
+        };
+        Ok(Self {
+            ident,
+            key,
+            ty,
+            missing,
+        })
+    }
+}
+
+/// The `T` of an `Option<T>` field.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(input.span(), "only structs are supported"));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new(
+            input.span(),
+            "only structs with named fields are supported",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "generic structs are not supported",
+        ));
+    }
+    let fields = named
+        .named
+        .iter()
+        .map(Field::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let name = &input.ident;
+    let visitor = format_ident!("__{}Visitor", name);
+    let zv = quote!(::ashpd::zvariant);
+    let serde = quote!(#zv::export::serde);
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let keys = fields.iter().map(|f| &f.key);
+    let types = fields.iter().map(|f| &f.ty);
+    let values = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let key = &field.key;
+        match &field.missing {
+            Missing::Error => quote! {
+                #ident.ok_or_else(|| <M::Error as #serde::de::Error>::missing_field(#key))?
+            },
+            Missing::None => quote!(#ident),
+            Missing::Default => quote!(#ident.unwrap_or_default()),
+            Missing::With(path) => quote!(#ident.unwrap_or_else(#path)),
+        }
+    });
+
+    Ok(quote! {
+        impl<'de> #serde::de::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: #serde::de::Deserializer<'de>,
+            {
+                struct #visitor;
+
+                impl<'de> #serde::de::Visitor<'de> for #visitor {
+                    type Value = #name;
+
+                    fn expecting(
+                        &self,
+                        formatter: &mut ::std::fmt::Formatter,
+                    ) -> ::std::fmt::Result {
+                        formatter.write_str("the a{sv} results of a portal response")
+                    }
+
+                    fn visit_map<M>(
+                        self,
+                        mut access: M,
+                    ) -> ::std::result::Result<Self::Value, M::Error>
+                    where
+                        M: #serde::de::MapAccess<'de>,
+                    {
+                        #( let mut #idents = ::std::option::Option::None; )*
+                        while let ::std::option::Option::Some(key) = access.next_key::<&str>()? {
+                            match key {
+                                #(
+                                    #keys => {
+                                        #idents = ::std::option::Option::Some(
+                                            access.next_value::<#zv::DeserializeValue<#types>>()?.0,
+                                        );
+                                    }
+                                )*
+                                _ => {
+                                    access.next_value::<#zv::Value>()?;
+                                }
+                            }
+                        }
+                        ::std::result::Result::Ok(#name { #( #idents: #values, )* })
+                    }
+                }
+
+                deserializer.deserialize_map(#visitor)
+            }
+        }
+
+        impl #zv::Type for #name {
+            fn signature() -> #zv::Signature<'static> {
+                #zv::Signature::from_static_str_unchecked("a{sv}")
+            }
+        }
+    })
+}
@@ -0,0 +1,231 @@
+//! Benchmarks of the request round trips and of the signals dispatch, against
+//! mock portals served on a private bus.
+//!
+//! `dbus-daemon` has to be installed, nothing is measured otherwise.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use ashpd::desktop::{location::LocationProxy, screenshot::Screenshot};
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures_util::StreamExt;
+use zbus::{
+    interface,
+    message::Header,
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value},
+};
+
+const DESKTOP_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const DESKTOP_PATH: &str = "/org/freedesktop/portal/desktop";
+const SESSION_PATH: &str = "/org/freedesktop/portal/desktop/session/1_1/ashpd_bench";
+
+struct Daemon(Child);
+
+impl Daemon {
+    fn spawn() -> Option<(Self, String)> {
+        let child = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--nopidfile", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let mut daemon = Self(child);
+        let mut address = String::new();
+        BufReader::new(daemon.0.stdout.take()?)
+            .read_line(&mut address)
+            .ok()?;
+        Some((daemon, address.trim().to_owned()))
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Answers the screenshot requests right away.
+struct ScreenshotPortal;
+
+#[interface(name = "org.freedesktop.portal.Screenshot")]
+impl ScreenshotPortal {
+    async fn screenshot(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        _parent_window: &str,
+        options: HashMap<String, OwnedValue>,
+    ) -> OwnedObjectPath {
+        let sender = header
+            .sender()
+            .unwrap()
+            .trim_start_matches(':')
+            .replace('.', "_");
+        let token = String::try_from(options["handle_token"].try_clone().unwrap()).unwrap();
+        let path: OwnedObjectPath = ObjectPath::try_from(format!(
+            "/org/freedesktop/portal/desktop/request/{sender}/{token}"
+        ))
+        .unwrap()
+        .into();
+        // The request is subscribed to before the call, the response can be
+        // emitted before the reply.
+        let results = HashMap::from([("uri", Value::from("file:///screenshot.png"))]);
+        connection
+            .emit_signal(
+                None::<()>,
+                &path,
+                "org.freedesktop.portal.Request",
+                "Response",
+                &(0u32, results),
+            )
+            .await
+            .unwrap();
+        path
+    }
+
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        2
+    }
+}
+
+/// Only there for the version lookup, the updates are emitted by the
+/// benchmarks.
+struct LocationPortal;
+
+#[interface(name = "org.freedesktop.portal.Location")]
+impl LocationPortal {
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        1
+    }
+}
+
+async fn emit_location_updated(portal: &zbus::Connection) {
+    let location = HashMap::from([
+        ("Latitude", Value::from(48.85)),
+        ("Longitude", Value::from(2.35)),
+        ("Altitude", Value::from(35.0)),
+        ("Accuracy", Value::from(10.0)),
+        ("Speed", Value::from(1.5)),
+        ("Heading", Value::from(90.0)),
+        ("Description", Value::from("")),
+        ("Timestamp", Value::from((1_700_000_000u64, 0u64))),
+    ]);
+    portal
+        .emit_signal(
+            None::<()>,
+            DESKTOP_PATH,
+            "org.freedesktop.portal.Location",
+            "LocationUpdated",
+            &(
+                ObjectPath::from_static_str_unchecked(SESSION_PATH),
+                location,
+            ),
+        )
+        .await
+        .unwrap();
+}
+
+fn dispatch(c: &mut Criterion) {
+    let Some((_daemon, address)) = Daemon::spawn() else {
+        eprintln!("dbus-daemon is not available, skipping the benchmarks");
+        return;
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    // The streams remove their match rules on a runtime task when dropped.
+    let _guard = runtime.enter();
+    let (portal, connection) = runtime.block_on(async {
+        let portal = zbus::connection::Builder::address(address.as_str())
+            .unwrap()
+            .name(DESKTOP_DESTINATION)
+            .unwrap()
+            .serve_at(DESKTOP_PATH, ScreenshotPortal)
+            .unwrap()
+            .serve_at(DESKTOP_PATH, LocationPortal)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let connection = zbus::connection::Builder::address(address.as_str())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        (portal, connection)
+    });
+
+    c.bench_function("request_round_trip", |b| {
+        b.iter_custom(|iters| {
+            runtime.block_on(async {
+                let start = Instant::now();
+                for _ in 0..iters {
+                    Screenshot::request()
+                        .connection(connection.clone())
+                        .send()
+                        .await
+                        .unwrap()
+                        .response()
+                        .unwrap();
+                }
+                start.elapsed()
+            })
+        })
+    });
+
+    let mut updates = runtime.block_on(async {
+        LocationProxy::with_connection(connection.clone())
+            .await
+            .unwrap()
+            .receive_location_updated()
+            .await
+            .unwrap()
+    });
+
+    c.bench_function("signal_wakeup", |b| {
+        b.iter_custom(|iters| {
+            runtime.block_on(async {
+                let mut elapsed = Duration::ZERO;
+                for _ in 0..iters {
+                    let start = Instant::now();
+                    emit_location_updated(&portal).await;
+                    updates.next().await.unwrap();
+                    elapsed += start.elapsed();
+                }
+                elapsed
+            })
+        })
+    });
+
+    c.bench_function("signal_burst_100", |b| {
+        b.iter_custom(|iters| {
+            runtime.block_on(async {
+                let start = Instant::now();
+                for _ in 0..iters {
+                    for _ in 0..100 {
+                        emit_location_updated(&portal).await;
+                    }
+                    for _ in 0..100 {
+                        updates.next().await.unwrap();
+                    }
+                }
+                start.elapsed()
+            })
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(3));
+    targets = dispatch
+}
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+fn main() {
+    #[cfg(feature = "qt")]
+    qt();
+}
+
+/// Compiles the `cpp!` blocks of the Qt window identifier against the Qt found
+/// by `qttypes`.
+#[cfg(feature = "qt")]
+fn qt() {
+    let include = std::env::var("DEP_QT_INCLUDE_PATH").unwrap();
+    let version = std::env::var("DEP_QT_VERSION").unwrap();
+    let mut config = cpp_build::Config::new();
+    for flag in std::env::var("DEP_QT_COMPILE_FLAGS")
+        .unwrap()
+        .split_terminator(';')
+    {
+        config.flag(flag);
+    }
+    // The native interface is part of the private QtGui headers.
+    config
+        .include(&include)
+        .include(format!("{include}/QtGui/{version}"))
+        .include(format!("{include}/QtGui/{version}/QtGui"))
+        .include(format!("{include}/QtCore/{version}"))
+        .include(format!("{include}/QtCore/{version}/QtCore"))
+        .build("src/lib.rs");
+}
@@ -27,11 +27,13 @@ pub struct SelectedFiles {
 }
 
 impl SelectedFiles {
+    #[must_use]
     pub fn uri(mut self, value: url::Url) -> Self {
         self.uris.push(value);
         self
     }
 
+    #[must_use]
     pub fn choice(mut self, choice_key: &str, choice_value: &str) -> Self {
         self.choices
             .get_or_insert_with(Vec::new)
@@ -39,11 +41,13 @@ impl SelectedFiles {
         self
     }
 
+    #[must_use]
     pub fn current_filter(mut self, value: impl Into<Option<FileFilter>>) -> Self {
         self.current_filter = value.into();
         self
     }
 
+    #[must_use]
     pub fn writable(mut self, value: impl Into<Option<bool>>) -> Self {
         self.writable = value.into();
         self
@@ -60,6 +60,7 @@ pub mod background;
 pub mod email;
 pub mod file_chooser;
 pub mod lockdown;
+pub mod notification;
 pub mod permission_store;
 pub mod print;
 pub mod request;
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    backend::{MaybeAppID, Result},
+    desktop::{notification::Priority, Icon},
+    zbus::SignalContext,
+    zvariant::{DeserializeDict, OwnedValue, Type},
+    AppID,
+};
+
+#[derive(Debug, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct Notification {
+    title: Option<String>,
+    body: Option<String>,
+    icon: Option<Icon>,
+    priority: Option<Priority>,
+    #[zvariant(rename = "default-action")]
+    default_action: Option<String>,
+    #[zvariant(rename = "default-action-target")]
+    default_action_target: Option<OwnedValue>,
+    buttons: Option<Vec<Button>>,
+    category: Option<String>,
+}
+
+impl Notification {
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub fn icon(&self) -> Option<&Icon> {
+        self.icon.as_ref()
+    }
+
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    pub fn default_action(&self) -> Option<&str> {
+        self.default_action.as_deref()
+    }
+
+    pub fn default_action_target(&self) -> Option<&OwnedValue> {
+        self.default_action_target.as_ref()
+    }
+
+    pub fn buttons(&self) -> &[Button] {
+        self.buttons.as_deref().unwrap_or_default()
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+}
+
+#[derive(Debug, DeserializeDict, Type)]
+#[zvariant(signature = "dict")]
+pub struct Button {
+    label: String,
+    action: String,
+    target: Option<OwnedValue>,
+}
+
+impl Button {
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn target(&self) -> Option<&OwnedValue> {
+        self.target.as_ref()
+    }
+}
+
+#[async_trait]
+pub trait NotificationImpl: Send + Sync {
+    async fn add_notification(
+        &self,
+        app_id: Option<AppID>,
+        id: &str,
+        notification: Notification,
+    ) -> Result<()>;
+
+    async fn remove_notification(&self, app_id: Option<AppID>, id: &str) -> Result<()>;
+}
+
+pub struct NotificationInterface {
+    imp: Arc<dyn NotificationImpl>,
+    cnx: zbus::Connection,
+}
+
+impl NotificationInterface {
+    pub fn new(imp: impl NotificationImpl + 'static, cnx: zbus::Connection) -> Self {
+        Self {
+            imp: Arc::new(imp),
+            cnx,
+        }
+    }
+
+    pub async fn invoke_action(
+        &self,
+        app_id: &AppID,
+        id: &str,
+        action: &str,
+        parameter: Vec<OwnedValue>,
+    ) -> zbus::Result<()> {
+        let object_server = self.cnx.object_server();
+        let iface_ref = object_server
+            .interface::<_, Self>(crate::proxy::DESKTOP_PATH)
+            .await?;
+        Self::action_invoked(iface_ref.signal_context(), app_id, id, action, parameter).await
+    }
+}
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.Notification")]
+impl NotificationInterface {
+    #[zbus(property(emits_changed_signal = "const"), name = "version")]
+    fn version(&self) -> u32 {
+        1
+    }
+
+    async fn add_notification(
+        &self,
+        app_id: MaybeAppID,
+        id: &str,
+        notification: Notification,
+    ) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::AddNotification");
+
+        let response = self
+            .imp
+            .add_notification(app_id.inner(), id, notification)
+            .await;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::AddNotification returned {:#?}", response);
+        response
+    }
+
+    async fn remove_notification(&self, app_id: MaybeAppID, id: &str) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::RemoveNotification");
+
+        let response = self.imp.remove_notification(app_id.inner(), id).await;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Notification::RemoveNotification returned {:#?}", response);
+        response
+    }
+
+    #[zbus(signal)]
+    async fn action_invoked(
+        signal_ctxt: &SignalContext<'_>,
+        app_id: &str,
+        id: &str,
+        action: &str,
+        parameter: Vec<OwnedValue>,
+    ) -> zbus::Result<()>;
+}
@@ -39,10 +39,18 @@ impl Request {
         #[cfg(feature = "tracing")]
         tracing::debug!("{_method}");
         let (fut, abort_handle) = abortable(callback);
-        let close_cb = || {
-            tokio::spawn(async move {
-                RequestImpl::close(&*imp).await;
-            });
+        let close_cb = {
+            let cnx = cnx.clone();
+            move || {
+                cnx.executor()
+                    .spawn(
+                        async move {
+                            RequestImpl::close(&*imp).await;
+                        },
+                        "ashpd: close backend request",
+                    )
+                    .detach();
+            }
         };
         let request = Request::new(close_cb, path.clone(), abort_handle, cnx.clone());
         let server = cnx.object_server();
@@ -0,0 +1,129 @@
+//! Use the portals from a [Bevy](https://bevyengine.org) application.
+//!
+//! The [`PortalPlugin`] inserts a [`Portals`] resource that holds the
+//! connection the portals are used over and spawns the portal calls on Bevy's
+//! [`IoTaskPool`]. The resulting [`Task`]s can be stored in a component and
+//! polled from a system.
+//!
+//! **Note** Bevy's task pools don't run a Tokio runtime, the `async-std`
+//! feature has to be used instead of the default `tokio` one for the portal
+//! futures to make progress on them.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use ashpd::{
+//!     bevy::{window_identifier, PortalPlugin, Portals},
+//!     desktop::screenshot::Screenshot,
+//! };
+//! use bevy::{prelude::*, tasks::Task, window::{PrimaryWindow, RawHandleWrapper}};
+//!
+//! #[derive(Component)]
+//! struct ScreenshotTask(Task<ashpd::Result<Screenshot>>);
+//!
+//! fn take_screenshot(
+//!     mut commands: Commands,
+//!     portals: Res<Portals>,
+//!     window: Query<&RawHandleWrapper, With<PrimaryWindow>>,
+//! ) {
+//!     let handle = window.single().clone();
+//!     let connection = portals.clone();
+//!     let task = portals.spawn(async move {
+//!         Screenshot::request()
+//!             .connection(connection.connection().await?)
+//!             .identifier(window_identifier(&handle).await)
+//!             .send()
+//!             .await?
+//!             .response()
+//!     });
+//!     commands.spawn(ScreenshotTask(task));
+//! }
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins((DefaultPlugins, PortalPlugin::default()))
+//!         .add_systems(Startup, take_screenshot)
+//!         .run();
+//! }
+//! ```
+
+use std::future::Future;
+
+use bevy_app::{App, Plugin};
+use bevy_ecs::system::Resource;
+use bevy_tasks::{IoTaskPool, Task, TaskPool};
+use bevy_window::RawHandleWrapper;
+
+use crate::{proxy::Proxy, Error, WindowIdentifier};
+
+/// A plugin inserting the [`Portals`] resource.
+#[derive(Debug, Default)]
+pub struct PortalPlugin {
+    connection: Option<zbus::Connection>,
+}
+
+impl PortalPlugin {
+    /// Use `connection` for the portals.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn with_connection(connection: zbus::Connection) -> Self {
+        Self {
+            connection: Some(connection),
+        }
+    }
+}
+
+impl Plugin for PortalPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Portals {
+            connection: self.connection.clone(),
+        });
+    }
+}
+
+/// The resource to use the portals from the systems.
+#[derive(Resource, Debug, Clone)]
+pub struct Portals {
+    connection: Option<zbus::Connection>,
+}
+
+impl Portals {
+    /// The connection the portals are used over, to pass to their
+    /// `with_connection` constructor or the `connection` setter of the request
+    /// builders.
+    ///
+    /// The connection to the session bus shared by the portals is opened if no
+    /// connection was given to the [`PortalPlugin`].
+    pub async fn connection(&self) -> Result<zbus::Connection, Error> {
+        Ok(Proxy::connection_or_shared(self.connection.clone()).await?)
+    }
+
+    /// Spawn a portal call on the [`IoTaskPool`].
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = T> + Send + 'static,
+    ) -> Task<T> {
+        IoTaskPool::get_or_init(TaskPool::new).spawn(future)
+    }
+}
+
+/// Create a [`WindowIdentifier`] from the handles of a Bevy window, like the
+/// primary one.
+///
+/// Under Wayland, the surface of the window is exported, which is why the
+/// function is async.
+pub async fn window_identifier(handle: &RawHandleWrapper) -> WindowIdentifier {
+    WindowIdentifier::from_raw_handle(&handle.window_handle, Some(&handle.display_handle)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_inserts_portals() {
+        let mut app = App::new();
+        app.add_plugins(PortalPlugin::default());
+        assert!(app.world().contains_resource::<Portals>());
+    }
+}
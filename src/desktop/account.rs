@@ -15,16 +15,18 @@
 //!         .await?
 //!         .response()?;
 //!
-//!     println!("Name: {}", response.name());
-//!     println!("ID: {}", response.id());
+//!     println!("Name: {:?}", response.name());
+//!     println!("ID: {:?}", response.id());
 //!
 //!     Ok(())
 //! }
 //! ```
 
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+use enumflags2::{bitflags, BitFlags};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zbus::zvariant::{SerializeDict, Type};
 
-use super::HandleToken;
+use super::{HandleToken, ResponseResults};
 use crate::{desktop::request::Request, proxy::Proxy, Error, WindowIdentifier};
 
 #[derive(SerializeDict, Type, Debug, Default)]
@@ -34,13 +36,52 @@ struct UserInformationOptions {
     reason: Option<String>,
 }
 
-#[derive(Debug, DeserializeDict, SerializeDict, Type)]
+#[bitflags]
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[repr(u32)]
+/// A field of the [`UserInformation`].
+pub enum UserInformationField {
+    /// The user identifier.
+    Id,
+    /// The user name.
+    Name,
+    /// The user image.
+    Image,
+}
+
+#[derive(Debug, ResponseResults, SerializeDict)]
 /// The response of a [`UserInformationRequest`] request.
-#[zvariant(signature = "dict")]
+///
+/// The fields are optional, as the backends can omit them, for example the
+/// image when the user has no avatar.
 pub struct UserInformation {
-    id: String,
-    name: String,
-    image: url::Url,
+    id: Option<String>,
+    name: Option<String>,
+    #[response(default)]
+    image: ImageUri,
+}
+
+/// The image URI, which the backends leave empty when the user has no avatar.
+#[derive(Debug, Default, Type)]
+#[zvariant(signature = "s")]
+struct ImageUri(Option<url::Url>);
+
+impl Serialize for ImageUri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_ref().map_or("", url::Url::as_str))
+    }
+}
+
+impl<'de> Deserialize<'de> for ImageUri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let uri = String::deserialize(deserializer)?;
+        if uri.is_empty() {
+            return Ok(Self(None));
+        }
+        url::Url::parse(&uri)
+            .map(|uri| Self(Some(uri)))
+            .map_err(serde::de::Error::custom)
+    }
 }
 
 impl UserInformation {
@@ -49,25 +90,38 @@ impl UserInformation {
     /// Create a new instance of [`UserInformation`].
     pub fn new(id: &str, name: &str, image: url::Url) -> Self {
         Self {
-            id: id.to_owned(),
-            name: name.to_owned(),
-            image,
+            id: Some(id.to_owned()),
+            name: Some(name.to_owned()),
+            image: ImageUri(Some(image)),
         }
     }
 
     /// User identifier.
-    pub fn id(&self) -> &str {
-        &self.id
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
     }
 
     /// User name.
-    pub fn name(&self) -> &str {
-        &self.name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// User image uri, `None` when the user has no avatar.
+    pub fn image(&self) -> Option<&url::Url> {
+        self.image.0.as_ref()
     }
 
-    /// User image uri.
-    pub fn image(&self) -> &url::Url {
-        &self.image
+    /// Drops the received fields that are not part of `fields`.
+    fn keep_fields(&mut self, fields: BitFlags<UserInformationField>) {
+        if !fields.contains(UserInformationField::Id) {
+            self.id = None;
+        }
+        if !fields.contains(UserInformationField::Name) {
+            self.name = None;
+        }
+        if !fields.contains(UserInformationField::Image) {
+            self.image = ImageUri(None);
+        }
     }
 
     /// Creates a new builder-pattern struct instance to construct
@@ -81,9 +135,11 @@ impl UserInformation {
 
 struct AccountProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(request UserInformationRequest => "org.freedesktop.portal.Account");
+
 impl<'a> AccountProxy<'a> {
-    pub async fn new() -> Result<AccountProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Account").await?;
+    pub async fn with_connection(connection: zbus::Connection) -> Result<AccountProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Account").await?;
         Ok(Self(proxy))
     }
 
@@ -119,6 +175,8 @@ impl<'a> std::ops::Deref for AccountProxy<'a> {
 pub struct UserInformationRequest {
     options: UserInformationOptions,
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
+    fields: Option<BitFlags<UserInformationField>>,
 }
 
 impl UserInformationRequest {
@@ -129,6 +187,22 @@ impl UserInformationRequest {
         self
     }
 
+    #[must_use]
+    /// Drops the fields that are not part of `fields` from the response on the
+    /// client side, once it is received. Defaults to keeping all of them.
+    ///
+    /// **Note** the portal has no option to only share some of the fields, it
+    /// still sends all of them to the application, which only doesn't keep
+    /// the other ones around. The reason should tell the user which fields
+    /// are used.
+    pub fn keep_fields(
+        mut self,
+        fields: impl Into<Option<BitFlags<UserInformationField>>>,
+    ) -> Self {
+        self.fields = fields.into();
+        self
+    }
+
     #[must_use]
     /// Sets a window identifier.
     pub fn identifier(mut self, identifier: impl Into<Option<WindowIdentifier>>) -> Self {
@@ -136,9 +210,83 @@ impl UserInformationRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Build the [`UserInformation`].
     pub async fn send(self) -> Result<Request<UserInformation>, Error> {
-        let proxy = AccountProxy::new().await?;
-        proxy.user_information(&self.identifier, self.options).await
+        let proxy =
+            AccountProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
+        let mut request = proxy
+            .user_information(&self.identifier, self.options)
+            .await?;
+        if let (Some(fields), Some(information)) = (self.fields, request.response_mut()) {
+            information.keep_fields(fields);
+        }
+        Ok(request)
+    }
+
+    /// Sends the request and waits for the [`UserInformation`], the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<UserInformation, Error> {
+        self.send().await?.response()
+    }
+}
+
+#[doc(alias = "xdp_portal_get_user_information")]
+/// A handy wrapper around [`UserInformation::request`] that waits for the
+/// user's response.
+pub async fn user_information() -> Result<UserInformation, Error> {
+    UserInformation::request().send_and_wait().await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::{serialized::Context, to_bytes, Value, LE};
+
+    use super::*;
+
+    #[test]
+    fn user_information_fields() {
+        let mut results = HashMap::new();
+        results.insert("id", Value::from("bilelmoussaoui"));
+        results.insert("name", Value::from("Bilal Elmoussaoui"));
+        results.insert("image", Value::from(""));
+        let ctxt = Context::new_dbus(LE, 0);
+        let data = to_bytes(ctxt, &results).unwrap();
+        let (mut information, _) = data.deserialize::<UserInformation>().unwrap();
+        assert_eq!(information.id(), Some("bilelmoussaoui"));
+        assert_eq!(information.image(), None);
+
+        information.keep_fields(UserInformationField::Name.into());
+        assert_eq!(information.id(), None);
+        assert_eq!(information.name(), Some("Bilal Elmoussaoui"));
+
+        let data = to_bytes(ctxt, &HashMap::<&str, Value<'_>>::new()).unwrap();
+        let (information, _) = data.deserialize::<UserInformation>().unwrap();
+        assert_eq!(information.name(), None);
+        assert_eq!(information.image(), None);
+
+        results.insert("image", Value::from("file:///home/bilal/.face"));
+        let data = to_bytes(ctxt, &results).unwrap();
+        let (information, _) = data.deserialize::<UserInformation>().unwrap();
+        assert_eq!(
+            information.image().map(url::Url::as_str),
+            Some("file:///home/bilal/.face")
+        );
+
+        // An invalid URI is an error instead of a missing image.
+        results.insert("image", Value::from("not a uri"));
+        let data = to_bytes(ctxt, &results).unwrap();
+        assert!(data.deserialize::<UserInformation>().is_err());
     }
 }
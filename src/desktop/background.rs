@@ -29,11 +29,36 @@
 //!
 //! If no `command` is provided, the [`Exec`](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#exec-variables) line from the [desktop
 //! file](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#introduction) will be used.
+//!
+//! The applications currently running in the background can be listed, from
+//! outside of the sandbox, with [`BackgroundMonitor`].
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::background::BackgroundMonitor;
+//! use futures_util::StreamExt;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let monitor = BackgroundMonitor::new().await?;
+//!     for app in monitor.background_apps().await? {
+//!         println!("{}: {:?}", app.app_id(), app.message());
+//!     }
+//!
+//!     let mut changes = monitor.receive_background_apps_changed().await?;
+//!     while let Some(apps) = changes.next().await {
+//!         println!("{} apps running in the background", apps.len());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
 
+use std::collections::HashMap;
+
+use futures_util::Stream;
 use serde::Serialize;
-use zbus::zvariant::{DeserializeDict, SerializeDict, Type};
+use zbus::zvariant::{self, SerializeDict, Type, Value};
 
-use super::{HandleToken, Request};
+use super::{HandleToken, Request, ResponseResults};
 use crate::{proxy::Proxy, Error, WindowIdentifier};
 
 #[derive(SerializeDict, Type, Debug, Default)]
@@ -48,9 +73,8 @@ struct BackgroundOptions {
     command: Option<Vec<String>>,
 }
 
-#[derive(DeserializeDict, Type, Debug)]
+#[derive(ResponseResults, Debug)]
 /// The response of a [`BackgroundRequest`] request.
-#[zvariant(signature = "dict")]
 pub struct Background {
     background: bool,
     autostart: bool,
@@ -90,10 +114,21 @@ struct SetStatusOptions {
 #[doc(alias = "org.freedesktop.portal.Background")]
 pub struct BackgroundProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(BackgroundProxy, "org.freedesktop.portal.Background");
+
 impl<'a> BackgroundProxy<'a> {
     /// Create a new instance of [`BackgroundProxy`].
     pub async fn new() -> Result<BackgroundProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Background").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`BackgroundProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<BackgroundProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Background").await?;
         Ok(Self(proxy))
     }
 
@@ -112,7 +147,6 @@ impl<'a> BackgroundProxy<'a> {
     /// # Specifications
     ///
     /// See also [`SetStatus`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Background.html#org-freedesktop-portal-background-setstatus).
-
     pub async fn set_status(&self, message: &str) -> Result<(), Error> {
         self.0
             .call_versioned(
@@ -155,6 +189,7 @@ impl<'a> std::ops::Deref for BackgroundProxy<'a> {
 #[derive(Debug, Default)]
 pub struct BackgroundRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     options: BackgroundOptions,
 }
 
@@ -166,6 +201,15 @@ impl BackgroundRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     #[must_use]
     /// Sets whether to auto start the application or not.
     pub fn auto_start(mut self, auto_start: impl Into<Option<bool>>) -> Self {
@@ -203,9 +247,157 @@ impl BackgroundRequest {
 
     /// Build the [`Background`].
     pub async fn send(self) -> Result<Request<Background>, Error> {
-        let proxy = BackgroundProxy::new().await?;
+        let proxy =
+            BackgroundProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy
             .request_background(&self.identifier, self.options)
             .await
     }
+
+    /// Sends the request and waits for the [`Background`], the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<Background, Error> {
+        self.send().await?.response()
+    }
+}
+
+/// An application running in the background, as reported by
+/// [`BackgroundMonitor::background_apps`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundApp {
+    app_id: String,
+    instance: String,
+    message: Option<String>,
+}
+
+impl BackgroundApp {
+    /// The application ID.
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// The Flatpak instance ID of the running application.
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+
+    /// The status message set by the application with
+    /// [`BackgroundProxy::set_status`], if any.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+impl<'a> TryFrom<Value<'a>> for BackgroundApp {
+    type Error = zvariant::Error;
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        let mut fields = HashMap::<String, Value<'a>>::try_from(value)?;
+        let mut field = |name: &str| fields.remove(name).map(Value::downcast::<String>);
+        Ok(Self {
+            app_id: field("app_id").ok_or(zvariant::Error::IncorrectType)??,
+            instance: field("instance").ok_or(zvariant::Error::IncorrectType)??,
+            message: field("message").transpose()?,
+        })
+    }
+}
+
+/// The interface provides information about the applications running in the
+/// background. It is meant to be used by the desktop environment, task-manager
+/// style applications and other applications running outside of the sandbox.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.background.Monitor`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.background.Monitor.html).
+#[derive(Debug)]
+#[doc(alias = "org.freedesktop.background.Monitor")]
+pub struct BackgroundMonitor<'a>(Proxy<'a>);
+
+crate::proxy::impl_portal!(
+    BackgroundMonitor,
+    "org.freedesktop.background.Monitor",
+    BACKGROUND_MONITOR_DESTINATION,
+    BACKGROUND_MONITOR_PATH
+);
+
+impl<'a> BackgroundMonitor<'a> {
+    /// Create a new instance of [`BackgroundMonitor`].
+    pub async fn new() -> Result<BackgroundMonitor<'a>, Error> {
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`BackgroundMonitor`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<BackgroundMonitor<'a>, Error> {
+        let proxy =
+            Proxy::new_background_monitor(connection, "org.freedesktop.background.Monitor").await?;
+        Ok(Self(proxy))
+    }
+
+    /// The list of applications currently running in the background.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`BackgroundApps`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.background.Monitor.html#org-freedesktop-background-monitor-backgroundapps).
+    #[doc(alias = "BackgroundApps")]
+    pub async fn background_apps(&self) -> Result<Vec<BackgroundApp>, Error> {
+        self.0.property("BackgroundApps").await
+    }
+
+    /// Emitted when the list of applications running in the background
+    /// changes.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`BackgroundApps`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.background.Monitor.html#org-freedesktop-background-monitor-backgroundapps).
+    pub async fn receive_background_apps_changed(
+        &self,
+    ) -> Result<impl Stream<Item = Vec<BackgroundApp>> + Unpin + '_, Error> {
+        Ok(self.0.receive_property_changed("BackgroundApps").await)
+    }
+}
+
+impl<'a> std::ops::Deref for BackgroundMonitor<'a> {
+    type Target = zbus::Proxy<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_value(fields: &[(&'static str, &'static str)]) -> Value<'static> {
+        let fields: HashMap<&str, Value<'_>> = fields
+            .iter()
+            .map(|(key, value)| (*key, Value::from(*value)))
+            .collect();
+        Value::from(fields)
+    }
+
+    #[test]
+    fn background_app_from_value() {
+        let app = BackgroundApp::try_from(app_value(&[
+            ("app_id", "org.gnome.Fractal"),
+            ("instance", "2239165983"),
+        ]))
+        .unwrap();
+        assert_eq!(app.app_id(), "org.gnome.Fractal");
+        assert_eq!(app.instance(), "2239165983");
+        assert_eq!(app.message(), None);
+
+        let app = BackgroundApp::try_from(app_value(&[
+            ("app_id", "org.gnome.Fractal"),
+            ("instance", "2239165983"),
+            ("message", "Syncing"),
+        ]))
+        .unwrap();
+        assert_eq!(app.message(), Some("Syncing"));
+
+        assert!(BackgroundApp::try_from(app_value(&[("instance", "2239165983")])).is_err());
+    }
 }
@@ -9,21 +9,23 @@
 //! pub async fn run() -> ashpd::Result<()> {
 //!     let camera = Camera::new().await?;
 //!     if camera.is_present().await? {
-//!         camera.request_access().await?;
-//!         let remote_fd = camera.open_pipe_wire_remote().await?;
-//!         // pass the remote fd to GStreamer for example
+//!         if let Some(remote_fd) = camera.request_access_and_open().await? {
+//!             // pass the remote fd to GStreamer for example
+//!         }
 //!     }
 //!     Ok(())
 //! }
 //! ```
 
-use std::{collections::HashMap, os::fd::OwnedFd};
+#[cfg(feature = "pipewire")]
+use std::collections::HashMap;
+use std::os::fd::OwnedFd;
 
 #[cfg(feature = "pipewire")]
 use pipewire::{context::Context, main_loop::MainLoop};
-use zbus::zvariant::{self, SerializeDict, Type, Value};
+use zbus::zvariant::{self, SerializeDict, Type};
 
-use super::{HandleToken, Request};
+use super::{EmptyOptions, HandleToken, Request, ResponseError};
 use crate::{proxy::Proxy, Error};
 
 #[derive(SerializeDict, Type, Debug, Default)]
@@ -40,10 +42,19 @@ struct CameraAccessOptions {
 #[doc(alias = "org.freedesktop.portal.Camera")]
 pub struct Camera<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(Camera, "org.freedesktop.portal.Camera");
+
 impl<'a> Camera<'a> {
     /// Create a new instance of [`Camera`].
     pub async fn new() -> Result<Camera<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Camera").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Camera`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Camera<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Camera").await?;
         Ok(Self(proxy))
     }
 
@@ -76,7 +87,7 @@ impl<'a> Camera<'a> {
     pub async fn open_pipe_wire_remote(&self) -> Result<OwnedFd, Error> {
         // `options` parameter doesn't seems to be used yet
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/camera.c#L178
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         let fd = self
             .0
             .call::<zvariant::OwnedFd>("OpenPipeWireRemote", &options)
@@ -84,6 +95,27 @@ impl<'a> Camera<'a> {
         Ok(fd.into())
     }
 
+    /// Requests an access to the camera and opens a file descriptor to the
+    /// PipeWire remote if it was granted.
+    ///
+    /// # Returns
+    ///
+    /// File descriptor of an open PipeWire remote, or `None` if the user
+    /// denied the access. The other failures of the request, like
+    /// [`ResponseError::Other`], are returned as errors.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`AccessCamera`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Camera.html#org-freedesktop-portal-camera-accesscamera)
+    /// and [`OpenPipeWireRemote`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Camera.html#org-freedesktop-portal-camera-openpipewireremote).
+    pub async fn request_access_and_open(&self) -> Result<Option<OwnedFd>, Error> {
+        match self.request_access().await?.response() {
+            Ok(()) => Ok(Some(self.open_pipe_wire_remote().await?)),
+            Err(Error::Response(ResponseError::Cancelled)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     /// A boolean stating whether there is any cameras available.
     ///
     /// # Specifications
@@ -240,11 +272,14 @@ pub async fn pipewire_streams(fd: OwnedFd) -> Result<Vec<Stream>, pipewire::Erro
 #[cfg_attr(docsrs, doc(cfg(not(feature = "pipewire"))))]
 /// Request access to the camera and return a file descriptor if one is
 /// available.
+///
+/// `None` is returned both when no camera is present and when the user denied
+/// the access. Use [`Camera::is_present`] and
+/// [`Camera::request_access_and_open`] to tell them apart.
 pub async fn request() -> Result<Option<OwnedFd>, Error> {
     let proxy = Camera::new().await?;
-    proxy.request_access().await?;
     if proxy.is_present().await? {
-        Ok(Some(proxy.open_pipe_wire_remote().await?))
+        proxy.request_access_and_open().await
     } else {
         Ok(None)
     }
@@ -254,14 +289,89 @@ pub async fn request() -> Result<Option<OwnedFd>, Error> {
 #[cfg_attr(docsrs, doc(cfg(feature = "pipewire")))]
 /// Request access to the camera and return a file descriptor and a list of the
 /// available streams, one per camera.
+///
+/// `None` is returned both when no camera is present and when the user denied
+/// the access. Use [`Camera::is_present`] and
+/// [`Camera::request_access_and_open`] to tell them apart.
 pub async fn request() -> Result<Option<(OwnedFd, Vec<Stream>)>, Error> {
     let proxy = Camera::new().await?;
-    proxy.request_access().await?;
-    if proxy.is_present().await? {
-        let fd = proxy.open_pipe_wire_remote().await?;
-        let streams = pipewire_streams(fd.try_clone()?).await?;
-        Ok(Some((fd, streams)))
-    } else {
-        Ok(None)
+    if !proxy.is_present().await? {
+        return Ok(None);
+    }
+    match proxy.request_access_and_open().await? {
+        Some(fd) => {
+            let streams = pipewire_streams(fd.try_clone()?).await?;
+            Ok(Some((fd, streams)))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::{
+        interface,
+        message::Header,
+        zvariant::{OwnedObjectPath, OwnedValue},
+    };
+
+    use super::*;
+    use crate::{
+        desktop::ResponseType,
+        mock::{self, MockBus},
+    };
+
+    /// Answers the access requests with the same response.
+    struct CameraPortal(ResponseType);
+
+    #[interface(name = "org.freedesktop.portal.Camera")]
+    impl CameraPortal {
+        async fn access_camera(
+            &self,
+            #[zbus(header)] header: Header<'_>,
+            #[zbus(connection)] connection: &zbus::Connection,
+            options: HashMap<String, OwnedValue>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            let path = mock::request_path(&header, &options)?;
+            let results = HashMap::<&str, OwnedValue>::new();
+            mock::respond(connection, &path, self.0, &results).await?;
+            Ok(path)
+        }
+    }
+
+    fn access(response: ResponseType) -> Option<Result<Option<OwnedFd>, Error>> {
+        let mut bus = MockBus::new().ok()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        Some(runtime.block_on(async {
+            bus.serve(CameraPortal(response)).await.unwrap();
+            let connection = bus.connect().await.unwrap();
+            Camera::with_connection(connection)
+                .await
+                .unwrap()
+                .request_access_and_open()
+                .await
+        }))
+    }
+
+    #[test]
+    fn denied_access() {
+        let Some(result) = access(ResponseType::Cancelled) else {
+            // No bus to run the test against.
+            return;
+        };
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[test]
+    fn failed_access() {
+        let Some(result) = access(ResponseType::Other) else {
+            return;
+        };
+        assert!(matches!(result, Err(Error::Response(ResponseError::Other))));
     }
 }
@@ -3,12 +3,10 @@
 //! The portal is mostly meant to be used along with
 //! [`RemoteDesktop`]
 
-use std::collections::HashMap;
-
 use futures_util::{Stream, StreamExt};
-use zbus::zvariant::{DeserializeDict, OwnedFd, OwnedObjectPath, SerializeDict, Type, Value};
+use zbus::zvariant::{DeserializeDict, OwnedFd, OwnedObjectPath, SerializeDict, Type};
 
-use super::{remote_desktop::RemoteDesktop, Session};
+use super::{remote_desktop::RemoteDesktop, EmptyOptions, Session};
 use crate::{proxy::Proxy, Result};
 
 #[derive(Debug, Type, SerializeDict)]
@@ -41,11 +39,20 @@ impl SelectionOwnerChanged {
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Clipboard`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Clipboard.html).
 pub struct Clipboard<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(Clipboard, "org.freedesktop.portal.Clipboard");
+
 impl<'a> Clipboard<'a> {
     /// Create a new instance of [`Clipboard`].
     pub async fn new() -> Result<Clipboard<'a>> {
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Clipboard`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Clipboard<'a>> {
         Ok(Self(
-            Proxy::new_desktop("org.freedesktop.portal.Clipboard").await?,
+            Proxy::new_desktop(connection, "org.freedesktop.portal.Clipboard").await?,
         ))
     }
 
@@ -54,7 +61,7 @@ impl<'a> Clipboard<'a> {
     /// See also [`RequestClipboard`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Clipboard.html#org-freedesktop-portal-clipboard-requestclipboard).
     #[doc(alias = "RequestClipboard")]
     pub async fn request(&self, session: &Session<'_, RemoteDesktop<'_>>) -> Result<()> {
-        let options: HashMap<&str, Value<'_>> = HashMap::default();
+        let options = EmptyOptions::default();
         self.0
             .call_method("RequestClipboard", &(session, options))
             .await?;
@@ -71,7 +78,9 @@ impl<'a> Clipboard<'a> {
         mime_types: &[&str],
     ) -> Result<()> {
         let options = SetSelectionOptions { mime_types };
-        self.0.call("SetSelection", &(session, options)).await?;
+        self.0
+            .call::<()>("SetSelection", &(session, options))
+            .await?;
 
         Ok(())
     }
@@ -130,12 +139,18 @@ impl<'a> Clipboard<'a> {
     #[doc(alias = "SelectionOwnerChanged")]
     pub async fn receive_selection_owner_changed(
         &self,
-    ) -> Result<impl Stream<Item = (Session<RemoteDesktop>, SelectionOwnerChanged)>> {
-        Ok(self
+    ) -> Result<impl Stream<Item = (Session<'_, RemoteDesktop<'_>>, SelectionOwnerChanged)> + Unpin>
+    {
+        let connection = self.0.connection().clone();
+        let stream = self
             .0
             .signal::<(OwnedObjectPath, SelectionOwnerChanged)>("SelectionOwnerChanged")
             .await?
-            .filter_map(|(p, o)| async move { Session::new(p).await.map(|s| (s, o)).ok() }))
+            .filter_map(move |(p, o)| {
+                let connection = connection.clone();
+                async move { Session::new(connection, p).await.map(|s| (s, o)).ok() }
+            });
+        Ok(Box::pin(stream))
     }
 
     /// # Specifications
@@ -144,17 +159,22 @@ impl<'a> Clipboard<'a> {
     #[doc(alias = "SelectionTransfer")]
     pub async fn receive_selection_transfer(
         &self,
-    ) -> Result<impl Stream<Item = (Session<RemoteDesktop>, String, u32)>> {
-        Ok(self
+    ) -> Result<impl Stream<Item = (Session<'_, RemoteDesktop<'_>>, String, u32)> + Unpin> {
+        let connection = self.0.connection().clone();
+        let stream = self
             .0
             .signal::<(OwnedObjectPath, String, u32)>("SelectionTransfer")
             .await?
-            .filter_map(|(p, mime_type, serial)| async move {
-                Session::new(p)
-                    .await
-                    .map(|session| (session, mime_type, serial))
-                    .ok()
-            }))
+            .filter_map(move |(p, mime_type, serial)| {
+                let connection = connection.clone();
+                async move {
+                    Session::new(connection, p)
+                        .await
+                        .map(|session| (session, mime_type, serial))
+                        .ok()
+                }
+            });
+        Ok(Box::pin(stream))
     }
 }
 
@@ -46,9 +46,20 @@ impl Color {
     pub fn blue(&self) -> f64 {
         self.color.2
     }
+
+    /// Formats the color as a CSS `rgb()` color, like `rgb(255 128 0)`.
+    pub fn to_css(&self) -> String {
+        let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "rgb({} {} {})",
+            channel(self.red()),
+            channel(self.green()),
+            channel(self.blue())
+        )
+    }
 }
 
-#[cfg(feature = "gtk4")]
+#[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
 impl From<Color> for gtk4::gdk::RGBA {
     fn from(color: Color) -> Self {
         gtk4::gdk::RGBA::builder()
@@ -59,6 +70,13 @@ impl From<Color> for gtk4::gdk::RGBA {
     }
 }
 
+#[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+impl From<gtk4::gdk::RGBA> for Color {
+    fn from(rgba: gtk4::gdk::RGBA) -> Self {
+        Self::new(rgba.red().into(), rgba.green().into(), rgba.blue().into())
+    }
+}
+
 impl std::fmt::Debug for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Color")
@@ -79,3 +97,14 @@ impl std::fmt::Display for Color {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn css() {
+        assert_eq!(Color::new(1.0, 0.5, 0.0).to_css(), "rgb(255 128 0)");
+        assert_eq!(Color::new(-0.1, 0.2, 1.2).to_css(), "rgb(0 51 255)");
+    }
+}
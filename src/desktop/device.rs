@@ -100,10 +100,19 @@ impl FromStr for Device {
 #[doc(alias = "org.freedesktop.portal.Device")]
 pub struct DeviceProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(DeviceProxy, "org.freedesktop.portal.Device");
+
 impl<'a> DeviceProxy<'a> {
     /// Create a new instance of [`DeviceProxy`].
     pub async fn new() -> Result<DeviceProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Device").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`DeviceProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<DeviceProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Device").await?;
         Ok(Self(proxy))
     }
 
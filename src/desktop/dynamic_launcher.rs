@@ -50,14 +50,12 @@
 //! }
 //! ```
 
-use std::collections::HashMap;
-
 use enumflags2::{bitflags, BitFlags};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{self, DeserializeDict, OwnedValue, SerializeDict, Type, Value};
 
-use super::{HandleToken, Icon, Request};
+use super::{EmptyOptions, HandleToken, Icon, Request};
 use crate::{proxy::Proxy, ActivationToken, Error, WindowIdentifier};
 
 #[bitflags]
@@ -126,12 +124,14 @@ pub struct PrepareInstallOptions {
 
 impl PrepareInstallOptions {
     /// Sets whether the dialog should be a modal.
+    #[must_use]
     pub fn modal(mut self, modal: impl Into<Option<bool>>) -> Self {
         self.modal = modal.into();
         self
     }
 
     /// Sets the launcher type.
+    #[must_use]
     pub fn launcher_type(mut self, launcher_type: LauncherType) -> Self {
         self.launcher_type = launcher_type;
         self
@@ -139,18 +139,21 @@ impl PrepareInstallOptions {
 
     /// The URL for a [`LauncherType::WebApplication`] otherwise it is not
     /// needed.
+    #[must_use]
     pub fn target<'a>(mut self, target: impl Into<Option<&'a str>>) -> Self {
         self.target = target.into().map(ToOwned::to_owned);
         self
     }
 
     /// Sets whether the name should be editable.
+    #[must_use]
     pub fn editable_name(mut self, editable_name: impl Into<Option<bool>>) -> Self {
         self.editable_name = editable_name.into();
         self
     }
 
     /// Sets whether the icon should be editable.
+    #[must_use]
     pub fn editable_icon(mut self, editable_icon: impl Into<Option<bool>>) -> Self {
         self.editable_icon = editable_icon.into();
         self
@@ -224,6 +227,121 @@ impl std::fmt::Display for UnexpectedIconError {
     }
 }
 
+/// The largest width and height of a bitmap icon accepted by the portal.
+pub const MAX_ICON_SIZE: u32 = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The icon doesn't satisfy the constraints enforced by the portal.
+pub enum InvalidIconError {
+    /// The icon is neither a PNG, a JPEG nor an SVG image.
+    UnsupportedFormat,
+    /// The width and height of the icon are not equal.
+    NotSquare(u32, u32),
+    /// The icon is larger than [`MAX_ICON_SIZE`].
+    TooLarge(u32),
+}
+
+impl std::error::Error for InvalidIconError {}
+impl std::fmt::Display for InvalidIconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedFormat => {
+                f.write_str("Unsupported icon format. Only PNG, JPEG and SVG are supported")
+            }
+            Self::NotSquare(width, height) => {
+                write!(f, "Expected a square icon but it is {width}x{height}")
+            }
+            Self::TooLarge(size) => write!(
+                f,
+                "Expected an icon of at most {MAX_ICON_SIZE}x{MAX_ICON_SIZE} but it is {size}x{size}"
+            ),
+        }
+    }
+}
+
+/// Checks that the icon satisfies the constraints enforced by the portal,
+/// without going through the portal.
+///
+/// The icon must be an [`Icon::Bytes`] of a PNG, JPEG or SVG image. Bitmap
+/// icons must be square and at most [`MAX_ICON_SIZE`] pixels wide.
+///
+/// # Returns
+///
+/// The detected [`IconType`].
+pub fn validate_icon(icon: &Icon) -> Result<IconType, Error> {
+    let Icon::Bytes(bytes) = icon else {
+        return Err(UnexpectedIconError.into());
+    };
+    let (type_, size) = if let Some(size) = png_size(bytes) {
+        (IconType::Png, Some(size))
+    } else if let Some(size) = jpeg_size(bytes) {
+        (IconType::Jpeg, Some(size))
+    } else if is_svg(bytes) {
+        (IconType::Svg, None)
+    } else {
+        return Err(InvalidIconError::UnsupportedFormat.into());
+    };
+    if let Some((width, height)) = size {
+        if width != height {
+            return Err(InvalidIconError::NotSquare(width, height).into());
+        }
+        if width > MAX_ICON_SIZE {
+            return Err(InvalidIconError::TooLarge(width).into());
+        }
+    }
+    Ok(type_)
+}
+
+fn png_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    // The IHDR chunk comes first and starts with the width and height.
+    if !bytes.starts_with(SIGNATURE) || bytes.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(bytes.get(20..24)?.try_into().ok()?);
+    Some((width, height))
+}
+
+fn jpeg_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        pos += 2;
+        match marker {
+            // Fill bytes
+            0xFF => pos -= 1,
+            // Markers without a payload
+            0x01 | 0xD0..=0xD7 => {}
+            // Start of frame markers, except DHT, JPG and DAC
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = u16::from_be_bytes(bytes.get(pos + 3..pos + 5)?.try_into().ok()?);
+                let width = u16::from_be_bytes(bytes.get(pos + 5..pos + 7)?.try_into().ok()?);
+                return Some((width.into(), height.into()));
+            }
+            _ => {
+                let len = u16::from_be_bytes(bytes.get(pos..pos + 2)?.try_into().ok()?);
+                pos += usize::from(len);
+            }
+        }
+    }
+}
+
+fn is_svg(bytes: &[u8]) -> bool {
+    let Ok(content) = std::str::from_utf8(bytes) else {
+        return false;
+    };
+    let content = content.trim_start_matches('\u{feff}').trim_start();
+    (content.starts_with("<?xml") || content.starts_with("<svg") || content.starts_with("<!--"))
+        && content.contains("<svg")
+}
+
 /// The interface lets sandboxed applications install launchers like Web
 /// Application from your browser or Steam.
 ///
@@ -232,16 +350,33 @@ impl std::fmt::Display for UnexpectedIconError {
 #[doc(alias = "org.freedesktop.portal.DynamicLauncher")]
 pub struct DynamicLauncherProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(
+    DynamicLauncherProxy,
+    "org.freedesktop.portal.DynamicLauncher"
+);
+
 impl<'a> DynamicLauncherProxy<'a> {
     /// Create a new instance of [`DynamicLauncherProxy`].
     pub async fn new() -> Result<DynamicLauncherProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.DynamicLauncher").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`DynamicLauncherProxy`] that uses
+    /// `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<DynamicLauncherProxy<'a>, Error> {
+        let proxy =
+            Proxy::new_desktop(connection, "org.freedesktop.portal.DynamicLauncher").await?;
         Ok(Self(proxy))
     }
 
-    /// *Note* Only `Icon::Bytes` is accepted.
+    /// *Note* Only `Icon::Bytes` is accepted. The icon is checked with
+    /// [`validate_icon`] before calling the portal.
     ///
-    ///  # Specifications
+    /// # Specifications
     ///
     /// See also [`PrepareInstall`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.DynamicLauncher.html#org-freedesktop-portal-dynamiclauncher-prepareinstall).
     #[doc(alias = "PrepareInstall")]
@@ -254,9 +389,7 @@ impl<'a> DynamicLauncherProxy<'a> {
         icon: Icon,
         options: PrepareInstallOptions,
     ) -> Result<Request<PrepareInstallResponse>, Error> {
-        if !icon.is_bytes() {
-            return Err(UnexpectedIconError {}.into());
-        }
+        validate_icon(&icon)?;
 
         self.0
             .request(
@@ -267,7 +400,8 @@ impl<'a> DynamicLauncherProxy<'a> {
             .await
     }
 
-    /// *Note* Only `Icon::Bytes` is accepted.
+    /// *Note* Only `Icon::Bytes` is accepted. The icon is checked with
+    /// [`validate_icon`] before calling the portal.
     ///
     /// # Specifications
     ///
@@ -275,12 +409,10 @@ impl<'a> DynamicLauncherProxy<'a> {
     #[doc(alias = "RequestInstallToken")]
     #[doc(alias = "xdp_portal_dynamic_launcher_request_install_token")]
     pub async fn request_install_token(&self, name: &str, icon: Icon) -> Result<String, Error> {
-        if !icon.is_bytes() {
-            return Err(UnexpectedIconError {}.into());
-        }
+        validate_icon(&icon)?;
 
         // No supported options for now
-        let options: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call::<String>("RequestInstallToken", &(name, icon.as_value(), options))
             .await
@@ -298,7 +430,7 @@ impl<'a> DynamicLauncherProxy<'a> {
         desktop_entry: &str,
     ) -> Result<(), Error> {
         // No supported options for now
-        let options: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call::<()>("Install", &(token, desktop_file_id, desktop_entry, options))
             .await
@@ -311,7 +443,7 @@ impl<'a> DynamicLauncherProxy<'a> {
     #[doc(alias = "xdp_portal_dynamic_launcher_uninstall")]
     pub async fn uninstall(&self, desktop_file_id: &str) -> Result<(), Error> {
         // No supported options for now
-        let options: HashMap<&str, zvariant::Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call::<()>("Uninstall", &(desktop_file_id, options))
             .await
@@ -375,4 +507,47 @@ mod test {
         let icon = vec![IconType::Png];
         assert_eq!(serde_json::to_string(&icon).unwrap(), "[\"png\"]");
     }
+
+    fn png(width: u32, height: u32) -> Icon {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes.extend([8, 6, 0, 0, 0]);
+        Icon::Bytes(bytes)
+    }
+
+    #[test]
+    fn icon_validation() {
+        assert_eq!(validate_icon(&png(128, 128)).unwrap(), IconType::Png);
+        assert!(matches!(
+            validate_icon(&png(128, 64)),
+            Err(Error::InvalidIcon(InvalidIconError::NotSquare(128, 64)))
+        ));
+        assert!(matches!(
+            validate_icon(&png(1024, 1024)),
+            Err(Error::InvalidIcon(InvalidIconError::TooLarge(1024)))
+        ));
+
+        // SOI, an APP0 segment, then a baseline SOF0 of 64x64
+        let jpeg = vec![
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+            0x40, 0x00, 0x40, 0x03,
+        ];
+        assert_eq!(validate_icon(&Icon::Bytes(jpeg)).unwrap(), IconType::Jpeg);
+
+        let svg = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg"/>"#;
+        assert_eq!(
+            validate_icon(&Icon::Bytes(svg.to_vec())).unwrap(),
+            IconType::Svg
+        );
+
+        assert!(matches!(
+            validate_icon(&Icon::Bytes(b"GIF89a".to_vec())),
+            Err(Error::InvalidIcon(InvalidIconError::UnsupportedFormat))
+        ));
+        assert!(matches!(
+            validate_icon(&Icon::with_names(["image-missing"])),
+            Err(Error::UnexpectedIcon)
+        ));
+    }
 }
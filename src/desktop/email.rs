@@ -50,10 +50,14 @@ struct EmailOptions {
 #[doc(alias = "org.freedesktop.portal.Email")]
 struct EmailProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(request EmailRequest => "org.freedesktop.portal.Email");
+
 impl<'a> EmailProxy<'a> {
-    /// Create a new instance of [`EmailProxy`].
-    pub async fn new() -> Result<EmailProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Email").await?;
+    /// Create a new instance of [`EmailProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<EmailProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Email").await?;
         Ok(Self(proxy))
     }
 
@@ -101,6 +105,7 @@ impl<'a> std::ops::Deref for EmailProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct EmailRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     options: EmailOptions,
 }
 
@@ -112,6 +117,15 @@ impl EmailRequest {
         self
     }
 
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    #[must_use]
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Sets the email address to send the email to.
     #[must_use]
     pub fn address<'a>(mut self, address: impl Into<Option<&'a str>>) -> Self {
@@ -200,7 +214,15 @@ impl EmailRequest {
 
     /// Send the request.
     pub async fn send(self) -> Result<Request<()>, Error> {
-        let proxy = EmailProxy::new().await?;
+        let proxy =
+            EmailProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy.compose(&self.identifier, self.options).await
     }
+
+    /// Sends the request and waits for the response, the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<(), Error> {
+        self.send().await?.response()
+    }
 }
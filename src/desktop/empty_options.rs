@@ -0,0 +1,32 @@
+use zbus::zvariant::{SerializeDict, Type};
+
+/// The options of the methods that don't define any yet.
+///
+/// Serializes to an empty `a{sv}` dictionary without building a map, as these
+/// are sent on hot paths like the input notifications of
+/// [`RemoteDesktop`](crate::desktop::remote_desktop::RemoteDesktop).
+#[derive(SerializeDict, Type, Debug, Default)]
+#[zvariant(signature = "dict")]
+pub(crate) struct EmptyOptions {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::{serialized::Context, to_bytes, Value, LE};
+
+    use super::*;
+
+    #[test]
+    fn empty_options_serialization() {
+        let ctxt = Context::new_dbus(LE, 0);
+        let map: HashMap<&str, Value<'_>> = HashMap::new();
+        assert_eq!(EmptyOptions::signature(), "a{sv}");
+        assert_eq!(
+            to_bytes(ctxt, &(EmptyOptions::default(), 1u32))
+                .unwrap()
+                .bytes(),
+            to_bytes(ctxt, &(map, 1u32)).unwrap().bytes()
+        );
+    }
+}
@@ -4,6 +4,9 @@
 //!
 //! Wrapper of the DBus interface: [`org.freedesktop.portal.FileChooser`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.FileChooser.html).
 //!
+//! The folders and files given to the requests are paths, a `file` URI can be
+//! passed after converting it with [`FilePath::try_from`](crate::FilePath).
+//!
 //! ### Examples
 //!
 //! #### Opening a file
@@ -79,7 +82,10 @@
 //! }
 //! ```
 
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -262,7 +268,7 @@ struct SaveFilesOptions {
     files: Option<Vec<FilePath>>,
 }
 
-#[derive(Debug, Type, DeserializeDict)]
+#[derive(Debug, Type, SerializeDict, DeserializeDict)]
 /// A response of [`OpenFileRequest`], [`SaveFileRequest`] or
 /// [`SaveFilesRequest`].
 #[zvariant(signature = "dict")]
@@ -292,19 +298,64 @@ impl SelectedFiles {
         self.uris.as_slice()
     }
 
+    /// The selected files as local paths, with their percent-encoding
+    /// decoded.
+    ///
+    /// URIs that don't use the `file` scheme are skipped.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.uris
+            .iter()
+            .filter_map(|uri| uri.to_file_path().ok())
+            .collect()
+    }
+
+    #[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+    /// The selected files as [`gio::File`](gtk4::gio::File)s.
+    pub fn files(&self) -> Vec<gtk4::gio::File> {
+        self.uris
+            .iter()
+            .map(|uri| gtk4::gio::File::for_uri(uri.as_str()))
+            .collect()
+    }
+
     /// The selected value of each choice as a tuple of (key, value)
     pub fn choices(&self) -> &[(String, String)] {
         self.choices.as_deref().unwrap_or_default()
     }
+
+    /// The selected value of each choice, keyed by the ID of the choice.
+    pub fn choices_map(&self) -> HashMap<&str, &str> {
+        self.choices()
+            .iter()
+            .map(|(id, value)| (id.as_str(), value.as_str()))
+            .collect()
+    }
+
+    /// The selected value of the choice with the given ID.
+    ///
+    /// The value of a [`Choice::boolean`] is either `"true"` or `"false"`.
+    pub fn choice(&self, id: &str) -> Option<&str> {
+        self.choices()
+            .iter()
+            .find(|(choice_id, _)| choice_id == id)
+            .map(|(_, value)| value.as_str())
+    }
 }
 
 #[doc(alias = "org.freedesktop.portal.FileChooser")]
 struct FileChooserProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(request OpenFileRequest, SaveFileRequest, SaveFilesRequest => "org.freedesktop.portal.FileChooser");
+
 impl<'a> FileChooserProxy<'a> {
-    /// Create a new instance of [`FileChooserProxy`].
-    pub async fn new() -> Result<FileChooserProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.FileChooser").await?;
+    /// Create a new instance of [`FileChooserProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<FileChooserProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.FileChooser").await?;
         Ok(Self(proxy))
     }
 
@@ -369,6 +420,7 @@ impl<'a> std::ops::Deref for FileChooserProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct OpenFileRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     title: String,
     options: OpenFileOptions,
 }
@@ -381,6 +433,15 @@ impl OpenFileRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Sets a title for the file chooser dialog.
     #[must_use]
     pub fn title<'a>(mut self, title: impl Into<Option<&'a str>>) -> Self {
@@ -403,6 +464,8 @@ impl OpenFileRequest {
     }
 
     /// Sets whether to allow multiple files selection.
+    ///
+    /// All the selected files are returned by [`SelectedFiles::uris`].
     #[must_use]
     pub fn multiple(mut self, multiple: impl Into<Option<bool>>) -> Self {
         self.options.multiple = multiple.into();
@@ -410,6 +473,10 @@ impl OpenFileRequest {
     }
 
     /// Sets whether to select directories or not.
+    ///
+    /// **Note** the option is ignored by the portals implementing a version
+    /// of the interface older than the 3rd one, which only let the user
+    /// select files.
     #[must_use]
     pub fn directory(mut self, directory: impl Into<Option<bool>>) -> Self {
         self.options.directory = directory.into();
@@ -468,11 +535,132 @@ impl OpenFileRequest {
 
     /// Send the request.
     pub async fn send(self) -> Result<Request<SelectedFiles>, Error> {
-        let proxy = FileChooserProxy::new().await?;
+        let proxy =
+            FileChooserProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy
             .open_file(&self.identifier, &self.title, self.options)
             .await
     }
+
+    /// Sends the request and waits for the [`SelectedFiles`], the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<SelectedFiles, Error> {
+        self.send().await?.response()
+    }
+
+    #[cfg(feature = "file_chooser_fallback")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "file_chooser_fallback")))]
+    /// Send the request, or show a GTK file dialog if the file chooser portal
+    /// is not available, like on hosts without a portals frontend running.
+    ///
+    /// The window identifier sent to the portal is derived from `parent`,
+    /// instead of the one set with [`OpenFileRequest::identifier`]. The
+    /// dialog is transient for `parent` and shown by the application itself,
+    /// this has to be called from the thread running the GTK main loop. The
+    /// choices are not supported by the dialog and are not returned, and the
+    /// user dismissing it results in a [`ResponseError::Cancelled`] error like
+    /// with the portal.
+    pub async fn send_with_fallback(
+        self,
+        parent: Option<&(impl gtk4::prelude::IsA<gtk4::Window> + Clone + 'static)>,
+    ) -> Result<SelectedFiles, Error> {
+        use gtk4::prelude::*;
+
+        let connection = Proxy::connection_or_shared(self.connection).await?;
+        let proxy = match FileChooserProxy::with_connection(connection).await {
+            Ok(proxy) => proxy,
+            Err(Error::PortalNotFound(_)) => {
+                return open_file_dialog(&self.title, self.options, parent).await
+            }
+            Err(err) => return Err(err),
+        };
+        let identifier = match parent {
+            Some(parent) => {
+                WindowIdentifier::from_native(parent.upcast_ref::<gtk4::Window>()).await
+            }
+            None => WindowIdentifier::default(),
+        };
+        proxy
+            .open_file(&identifier, &self.title, self.options)
+            .await?
+            .response()
+    }
+}
+
+/// Shows a GTK file dialog to open files.
+#[cfg(feature = "file_chooser_fallback")]
+async fn open_file_dialog(
+    title: &str,
+    options: OpenFileOptions,
+    parent: Option<&(impl gtk4::prelude::IsA<gtk4::Window> + Clone + 'static)>,
+) -> Result<SelectedFiles, Error> {
+    use gtk4::{gio, prelude::*};
+
+    use super::ResponseError;
+
+    let dialog = gtk4::FileDialog::builder()
+        .title(title)
+        .modal(options.modal.unwrap_or(true))
+        .build();
+    dialog.set_accept_label(options.accept_label.as_deref());
+    if let Some(folder) = &options.current_folder {
+        dialog.set_initial_folder(Some(&gio::File::for_path(folder)));
+    }
+    let filters = gio::ListStore::new::<gtk4::FileFilter>();
+    for filter in &options.filters {
+        let gtk_filter = gtk4::FileFilter::new();
+        gtk_filter.set_name(Some(filter.label()));
+        for (filter_type, value) in &filter.1 {
+            match filter_type {
+                FilterType::GlobPattern => gtk_filter.add_pattern(value),
+                FilterType::MimeType => gtk_filter.add_mime_type(value),
+            }
+        }
+        if options.current_filter.as_ref() == Some(filter) {
+            dialog.set_default_filter(Some(&gtk_filter));
+        }
+        filters.append(&gtk_filter);
+    }
+    if filters.n_items() > 0 {
+        dialog.set_filters(Some(&filters));
+    }
+
+    let list = |files: gio::ListModel| {
+        (0..files.n_items())
+            .filter_map(|position| files.item(position).and_downcast::<gio::File>())
+            .collect::<Vec<_>>()
+    };
+    let files = match (
+        options.directory.unwrap_or_default(),
+        options.multiple.unwrap_or_default(),
+    ) {
+        (false, false) => dialog.open_future(parent).await.map(|file| vec![file]),
+        (false, true) => dialog.open_multiple_future(parent).await.map(list),
+        (true, false) => dialog
+            .select_folder_future(parent)
+            .await
+            .map(|folder| vec![folder]),
+        (true, true) => dialog
+            .select_multiple_folders_future(parent)
+            .await
+            .map(list),
+    };
+    let files = files.map_err(|err| {
+        if err.matches(gtk4::DialogError::Dismissed) || err.matches(gtk4::DialogError::Cancelled) {
+            Error::Response(ResponseError::Cancelled)
+        } else {
+            Error::Response(ResponseError::Other)
+        }
+    })?;
+    let uris = files
+        .iter()
+        .filter_map(|file| url::Url::parse(&file.uri()).ok())
+        .collect();
+    Ok(SelectedFiles {
+        uris,
+        choices: None,
+    })
 }
 
 #[derive(Debug, Default)]
@@ -482,6 +670,7 @@ impl OpenFileRequest {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct SaveFilesRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     title: String,
     options: SaveFilesOptions,
 }
@@ -494,6 +683,15 @@ impl SaveFilesRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Sets a title for the file chooser dialog.
     #[must_use]
     pub fn title<'a>(mut self, title: impl Into<Option<&'a str>>) -> Self {
@@ -558,11 +756,19 @@ impl SaveFilesRequest {
 
     /// Send the request.
     pub async fn send(self) -> Result<Request<SelectedFiles>, Error> {
-        let proxy = FileChooserProxy::new().await?;
+        let proxy =
+            FileChooserProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy
             .save_files(&self.identifier, &self.title, self.options)
             .await
     }
+
+    /// Sends the request and waits for the [`SelectedFiles`], the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<SelectedFiles, Error> {
+        self.send().await?.response()
+    }
 }
 
 #[derive(Debug, Default)]
@@ -572,6 +778,7 @@ impl SaveFilesRequest {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct SaveFileRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     title: String,
     options: SaveFileOptions,
 }
@@ -584,6 +791,15 @@ impl SaveFileRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Sets a title for the file chooser dialog.
     #[must_use]
     pub fn title<'a>(mut self, title: impl Into<Option<&'a str>>) -> Self {
@@ -673,9 +889,52 @@ impl SaveFileRequest {
 
     /// Send the request.
     pub async fn send(self) -> Result<Request<SelectedFiles>, Error> {
-        let proxy = FileChooserProxy::new().await?;
+        let proxy =
+            FileChooserProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy
             .save_file(&self.identifier, &self.title, self.options)
             .await
     }
+
+    /// Sends the request and waits for the [`SelectedFiles`], the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<SelectedFiles, Error> {
+        self.send().await?.response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selected_files_paths() {
+        let files = SelectedFiles {
+            uris: vec![
+                url::Url::parse("file:///home/user/My%20Documents/report.pdf").unwrap(),
+                url::Url::parse("https://example.org/report.pdf").unwrap(),
+            ],
+            choices: None,
+        };
+        assert_eq!(
+            files.paths(),
+            vec![PathBuf::from("/home/user/My Documents/report.pdf")]
+        );
+    }
+
+    #[test]
+    fn selected_files_choices() {
+        let files = SelectedFiles {
+            uris: vec![],
+            choices: Some(vec![
+                ("encoding".to_owned(), "utf8".to_owned()),
+                ("reencode".to_owned(), "true".to_owned()),
+            ]),
+        };
+        assert_eq!(files.choice("encoding"), Some("utf8"));
+        assert_eq!(files.choice("reencode"), Some("true"));
+        assert_eq!(files.choice("unknown"), None);
+        assert_eq!(files.choices_map().len(), 2);
+    }
 }
@@ -76,10 +76,19 @@ enum RegisterStatus {
 #[doc(alias = "org.freedesktop.portal.GameMode")]
 pub struct GameMode<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(GameMode, "org.freedesktop.portal.GameMode");
+
 impl<'a> GameMode<'a> {
     /// Create a new instance of [`GameMode`].
     pub async fn new() -> Result<GameMode<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.GameMode").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`GameMode`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<GameMode<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.GameMode").await?;
         Ok(Self(proxy))
     }
 
@@ -1,7 +1,8 @@
 //! Register global shortcuts
 
-use std::{collections::HashMap, fmt::Debug, time::Duration};
+use std::{collections::HashMap, fmt, fmt::Debug, str::FromStr, time::Duration};
 
+use enumflags2::{bitflags, BitFlags};
 use futures_util::{Stream, TryFutureExt};
 use serde::{Deserialize, Serialize};
 use zbus::zvariant::{
@@ -11,6 +12,133 @@ use zbus::zvariant::{
 use super::{session::SessionPortal, HandleToken, Request, Session};
 use crate::{desktop::session::CreateSessionResponse, proxy::Proxy, Error, WindowIdentifier};
 
+#[bitflags]
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[repr(u32)]
+/// A modifier of a [`Trigger`].
+pub enum Modifier {
+    /// The Control key.
+    Ctrl,
+    /// The Alt key.
+    Alt,
+    /// The Shift key.
+    Shift,
+    /// The Num Lock key.
+    Num,
+    /// The Super, Windows or Command key.
+    Logo,
+}
+
+impl Modifier {
+    /// The name of the modifier in the shortcuts XDG specification.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Ctrl => "CTRL",
+            Self::Alt => "ALT",
+            Self::Shift => "SHIFT",
+            Self::Num => "NUM",
+            Self::Logo => "LOGO",
+        }
+    }
+}
+
+impl FromStr for Modifier {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" | "primary" => Ok(Self::Ctrl),
+            "alt" | "mod1" | "option" => Ok(Self::Alt),
+            "shift" => Ok(Self::Shift),
+            "num" | "numlock" | "mod2" => Ok(Self::Num),
+            "logo" | "super" | "meta" | "win" | "mod4" | "cmd" => Ok(Self::Logo),
+            _ => Err(Error::ParseError("Failed to parse modifier, invalid value")),
+        }
+    }
+}
+
+/// A shortcut trigger, a key pressed along with modifiers.
+///
+/// It is displayed in the syntax of the shortcuts XDG specification, like
+/// `CTRL+ALT+t`, to be passed to [`NewShortcut::trigger`].
+///
+/// It can be parsed from that syntax, but also from the trigger descriptions
+/// the portals return for [`Shortcut::triggers`], like `Ctrl+Alt+T`,
+/// `Meta+Shift+S` or `<Control><Alt>t`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trigger {
+    modifiers: BitFlags<Modifier>,
+    key: String,
+}
+
+impl Trigger {
+    /// Create a new [`Trigger`].
+    ///
+    /// # Arguments
+    ///
+    /// * `modifiers` - The modifiers held down.
+    /// * `key` - The name of the key, an XKB keysym name like `a` or `Return`.
+    pub fn new(modifiers: impl Into<BitFlags<Modifier>>, key: impl Into<String>) -> Self {
+        Self {
+            modifiers: modifiers.into(),
+            key: key.into(),
+        }
+    }
+
+    /// The modifiers held down.
+    pub fn modifiers(&self) -> BitFlags<Modifier> {
+        self.modifiers
+    }
+
+    /// The name of the key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in self.modifiers.iter() {
+            write!(f, "{}+", modifier.name())?;
+        }
+        f.write_str(&self.key)
+    }
+}
+
+impl FromStr for Trigger {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s.trim();
+        let mut modifiers = BitFlags::empty();
+        // The GTK accelerators syntax, `<Control><Alt>t`.
+        while let Some(accelerator) = rest.strip_prefix('<') {
+            let (modifier, after) = accelerator.split_once('>').ok_or(Error::ParseError(
+                "Failed to parse trigger, unclosed modifier",
+            ))?;
+            modifiers |= modifier.parse::<Modifier>()?;
+            rest = after;
+        }
+        // The `+` separated syntax, where the key can be a `+` itself.
+        let (prefix, key) = match rest.strip_suffix("++") {
+            Some(prefix) => (Some(prefix), "+"),
+            None if rest == "+" => (None, rest),
+            None => match rest.rsplit_once('+') {
+                Some((prefix, key)) => (Some(prefix), key),
+                None => (None, rest),
+            },
+        };
+        for modifier in prefix.into_iter().flat_map(|prefix| prefix.split('+')) {
+            modifiers |= modifier.trim().parse::<Modifier>()?;
+        }
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(Error::ParseError("Failed to parse trigger, invalid key"));
+        }
+        Ok(Self::new(modifiers, key))
+    }
+}
+
 #[derive(Clone, SerializeDict, Type, Debug, Default)]
 #[zvariant(signature = "dict")]
 struct NewShortcutInfo {
@@ -45,6 +173,13 @@ impl NewShortcut {
         self.1.preferred_trigger = preferred_trigger.into().map(ToOwned::to_owned);
         self
     }
+
+    /// Sets the preferred shortcut trigger from a [`Trigger`].
+    #[must_use]
+    pub fn trigger(mut self, trigger: impl Into<Option<Trigger>>) -> Self {
+        self.1.preferred_trigger = trigger.into().map(|trigger| trigger.to_string());
+        self
+    }
 }
 
 #[derive(Clone, DeserializeDict, Type, Debug, Default)]
@@ -80,6 +215,20 @@ impl Shortcut {
     pub fn trigger_description(&self) -> &str {
         &self.1.trigger_description
     }
+
+    /// The triggers parsed from the
+    /// [`trigger_description()`][`Shortcut::trigger_description`], for the
+    /// application to render them in its own way.
+    ///
+    /// The descriptions are meant to be read by users and differ between the
+    /// portals, the ones that can't be parsed are left out.
+    pub fn triggers(&self) -> Vec<Trigger> {
+        self.1
+            .trigger_description
+            .split(", ")
+            .filter_map(|trigger| trigger.parse().ok())
+            .collect()
+    }
 }
 
 /// Specified options for a [`GlobalShortcuts::create_session`] request.
@@ -211,10 +360,22 @@ impl ShortcutsChanged {
 #[doc(alias = "org.freedesktop.portal.GlobalShortcuts")]
 pub struct GlobalShortcuts<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(GlobalShortcuts, "org.freedesktop.portal.GlobalShortcuts");
+
 impl<'a> GlobalShortcuts<'a> {
     /// Create a new instance of [`GlobalShortcuts`].
     pub async fn new() -> Result<GlobalShortcuts<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.GlobalShortcuts").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`GlobalShortcuts`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<GlobalShortcuts<'a>, Error> {
+        let proxy =
+            Proxy::new_desktop(connection, "org.freedesktop.portal.GlobalShortcuts").await?;
         Ok(Self(proxy))
     }
 
@@ -230,7 +391,8 @@ impl<'a> GlobalShortcuts<'a> {
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(self.0.connection(), &options.session_handle_token)
+                .into_future(),
         )?;
         assert_eq!(proxy.path(), &request.response()?.session_handle.as_ref());
         Ok(proxy)
@@ -280,7 +442,7 @@ impl<'a> GlobalShortcuts<'a> {
     ///
     /// See also [`Activated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html#org-freedesktop-portal-globalshortcuts-activated).
     #[doc(alias = "Activated")]
-    pub async fn receive_activated(&self) -> Result<impl Stream<Item = Activated>, Error> {
+    pub async fn receive_activated(&self) -> Result<impl Stream<Item = Activated> + Unpin, Error> {
         self.0.signal("Activated").await
     }
 
@@ -290,7 +452,9 @@ impl<'a> GlobalShortcuts<'a> {
     ///
     /// See also [`Deactivated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.GlobalShortcuts.html#org-freedesktop-portal-globalshortcuts-deactivated).
     #[doc(alias = "Deactivated")]
-    pub async fn receive_deactivated(&self) -> Result<impl Stream<Item = Deactivated>, Error> {
+    pub async fn receive_deactivated(
+        &self,
+    ) -> Result<impl Stream<Item = Deactivated> + Unpin, Error> {
         self.0.signal("Deactivated").await
     }
 
@@ -303,7 +467,7 @@ impl<'a> GlobalShortcuts<'a> {
     #[doc(alias = "ShortcutsChanged")]
     pub async fn receive_shortcuts_changed(
         &self,
-    ) -> Result<impl Stream<Item = ShortcutsChanged>, Error> {
+    ) -> Result<impl Stream<Item = ShortcutsChanged> + Unpin, Error> {
         self.0.signal("ShortcutsChanged").await
     }
 }
@@ -317,3 +481,53 @@ impl<'a> std::ops::Deref for GlobalShortcuts<'a> {
 }
 
 impl SessionPortal for GlobalShortcuts<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_syntax() {
+        let trigger = Trigger::new(Modifier::Ctrl | Modifier::Alt, "t");
+        assert_eq!(trigger.to_string(), "CTRL+ALT+t");
+        assert_eq!("CTRL+ALT+t".parse::<Trigger>().unwrap(), trigger);
+        assert_eq!(Trigger::new(BitFlags::empty(), "F5").to_string(), "F5");
+    }
+
+    #[test]
+    fn trigger_descriptions() {
+        // KDE
+        let trigger = "Meta+Shift+S".parse::<Trigger>().unwrap();
+        assert_eq!(trigger.modifiers(), Modifier::Logo | Modifier::Shift);
+        assert_eq!(trigger.key(), "S");
+        // GTK accelerators
+        let trigger = "<Control><Alt>t".parse::<Trigger>().unwrap();
+        assert_eq!(trigger, Trigger::new(Modifier::Ctrl | Modifier::Alt, "t"));
+        // The plus key
+        let trigger = "Ctrl++".parse::<Trigger>().unwrap();
+        assert_eq!(trigger, Trigger::new(Modifier::Ctrl, "+"));
+        assert_eq!("+".parse::<Trigger>().unwrap().key(), "+");
+
+        assert!("Hyper+x".parse::<Trigger>().is_err());
+        assert!("Ctrl+".parse::<Trigger>().is_err());
+        assert!("<Control".parse::<Trigger>().is_err());
+    }
+
+    #[test]
+    fn shortcut_triggers() {
+        let shortcut = Shortcut(
+            "screenshot".to_owned(),
+            ShortcutInfo {
+                description: "Take a screenshot".to_owned(),
+                trigger_description: "Print, Ctrl+Print, Press something".to_owned(),
+            },
+        );
+        assert_eq!(
+            shortcut.triggers(),
+            [
+                Trigger::new(BitFlags::empty(), "Print"),
+                Trigger::new(Modifier::Ctrl, "Print")
+            ]
+        );
+    }
+}
@@ -1,11 +1,12 @@
 use std::{
     convert::TryFrom,
     fmt::{self, Debug, Display},
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde::{Deserialize, Serialize};
-use zbus::{names::OwnedMemberName, zvariant::Type};
+use zbus::zvariant::Type;
 
 /// A handle token is a DBus Object Path element, specified in the
 /// [`Request`](crate::desktop::Request)  or
@@ -14,9 +15,9 @@ use zbus::{names::OwnedMemberName, zvariant::Type};
 /// caller's unique name and token is the [`HandleToken`].
 ///
 /// A valid object path element must only contain the ASCII characters
-/// `[A-Z][a-z][0-9]_`
+/// `[A-Z][a-z][0-9]_` and can't be empty.
 #[derive(Serialize, Type)]
-pub struct HandleToken(OwnedMemberName);
+pub struct HandleToken(String);
 
 impl Display for HandleToken {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -32,6 +33,10 @@ impl Debug for HandleToken {
     }
 }
 
+/// Counts the generated tokens, so that no two of them are the same within the
+/// process even if the random part collides.
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
 impl Default for HandleToken {
     fn default() -> Self {
         let mut rng = thread_rng();
@@ -40,7 +45,8 @@ impl Default for HandleToken {
             .take(10)
             .map(char::from)
             .collect();
-        format!("ashpd_{token}").parse().unwrap()
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        format!("ashpd_{count}_{token}").parse().unwrap()
     }
 }
 
@@ -55,21 +61,58 @@ impl std::fmt::Display for HandleInvalidCharacter {
 
 impl std::error::Error for HandleInvalidCharacter {}
 
+/// The reasons a string is not a valid [`HandleToken`].
+#[derive(Debug)]
+pub enum HandleTokenError {
+    /// The token is empty.
+    Empty,
+    /// The token contains a character that is not allowed in an object path
+    /// element.
+    InvalidCharacter(HandleInvalidCharacter),
+}
+
+impl From<HandleInvalidCharacter> for HandleTokenError {
+    fn from(err: HandleInvalidCharacter) -> Self {
+        Self::InvalidCharacter(err)
+    }
+}
+
+impl std::fmt::Display for HandleTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("Empty handle token"),
+            Self::InvalidCharacter(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl std::error::Error for HandleTokenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Empty => None,
+            Self::InvalidCharacter(err) => Some(err),
+        }
+    }
+}
+
 impl std::str::FromStr for HandleToken {
-    type Err = HandleInvalidCharacter;
+    type Err = HandleTokenError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() {
+            return Err(HandleTokenError::Empty);
+        }
         for char in value.chars() {
             if !char.is_ascii_alphanumeric() && char != '_' {
-                return Err(HandleInvalidCharacter(char));
+                return Err(HandleInvalidCharacter(char).into());
             }
         }
-        Ok(Self(OwnedMemberName::try_from(value).unwrap()))
+        Ok(Self(value.to_owned()))
     }
 }
 
 impl TryFrom<String> for HandleToken {
-    type Error = HandleInvalidCharacter;
+    type Error = HandleTokenError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         value.parse::<Self>()
@@ -77,7 +120,7 @@ impl TryFrom<String> for HandleToken {
 }
 
 impl TryFrom<&str> for HandleToken {
-    type Error = HandleInvalidCharacter;
+    type Error = HandleTokenError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         value.parse::<Self>()
@@ -95,11 +138,12 @@ impl<'de> Deserialize<'de> for HandleToken {
             .map_err(|err| serde::de::Error::custom(err.to_string()))
     }
 }
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
 
-    use super::HandleToken;
+    use super::{HandleInvalidCharacter, HandleToken, HandleTokenError};
 
     #[test]
     fn handle_token() {
@@ -114,6 +158,23 @@ mod test {
 
         assert!(HandleToken::from_str("test_token").is_ok());
 
+        assert!(matches!(
+            HandleToken::from_str(""),
+            Err(HandleTokenError::Empty)
+        ));
+        assert!(matches!(
+            HandleToken::from_str("a-b"),
+            Err(HandleTokenError::InvalidCharacter(HandleInvalidCharacter(
+                '-'
+            )))
+        ));
+
+        assert!(HandleToken::from_str("1token").is_ok());
+
         HandleToken::default(); // ensure we don't panic
+        assert_ne!(
+            HandleToken::default().to_string(),
+            HandleToken::default().to_string()
+        );
     }
 }
@@ -1,3 +1,5 @@
+use std::os::fd::{AsFd, AsRawFd, OwnedFd};
+
 use serde::{
     de,
     ser::{Serialize, SerializeTuple},
@@ -7,7 +9,7 @@ use zbus::zvariant::{self, OwnedValue, Type, Value};
 
 use crate::Error;
 
-#[derive(Debug, PartialEq, Eq, Type)]
+#[derive(Debug, Type)]
 #[zvariant(signature = "(sv)")]
 /// A representation of an icon.
 ///
@@ -19,8 +21,27 @@ pub enum Icon {
     Names(Vec<String>),
     /// Icon bytes.
     Bytes(Vec<u8>),
+    /// A file descriptor of an icon, for example of a dynamically generated
+    /// image.
+    ///
+    /// Requires version 2 of the notification portal.
+    FileDescriptor(OwnedFd),
 }
 
+impl PartialEq for Icon {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Uri(a), Self::Uri(b)) => a == b,
+            (Self::Names(a), Self::Names(b)) => a == b,
+            (Self::Bytes(a), Self::Bytes(b)) => a == b,
+            (Self::FileDescriptor(a), Self::FileDescriptor(b)) => a.as_raw_fd() == b.as_raw_fd(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Icon {}
+
 impl Icon {
     /// Create an icon from a list of names.
     pub fn with_names<N>(names: impl IntoIterator<Item = N>) -> Self
@@ -30,11 +51,7 @@ impl Icon {
         Self::Names(names.into_iter().map(|name| name.to_string()).collect())
     }
 
-    pub(crate) fn is_bytes(&self) -> bool {
-        matches!(self, Self::Bytes(_))
-    }
-
-    pub(crate) fn inner_bytes(&self) -> Value {
+    pub(crate) fn inner_bytes(&self) -> Value<'_> {
         match self {
             Self::Bytes(bytes) => {
                 let mut array = zvariant::Array::new(u8::signature());
@@ -48,7 +65,7 @@ impl Icon {
         }
     }
 
-    pub(crate) fn as_value(&self) -> Value {
+    pub(crate) fn as_value(&self) -> Value<'_> {
         let tuple = match self {
             Self::Uri(uri) => ("file", Value::from(uri.as_str())),
             Self::Names(names) => {
@@ -61,6 +78,7 @@ impl Icon {
                 ("themed", Value::from(array))
             }
             Self::Bytes(_) => ("bytes", self.inner_bytes()),
+            Self::FileDescriptor(fd) => ("file-descriptor", Value::from(zvariant::Fd::from(fd))),
         };
         Value::new(tuple)
     }
@@ -90,6 +108,10 @@ impl Serialize for Icon {
                 tuple.serialize_element("bytes")?;
                 tuple.serialize_element(&self.inner_bytes())?;
             }
+            Self::FileDescriptor(fd) => {
+                tuple.serialize_element("file-descriptor")?;
+                tuple.serialize_element(&Value::from(zvariant::Fd::from(fd)))?;
+            }
         }
         tuple.end()
     }
@@ -125,6 +147,13 @@ impl<'de> Deserialize<'de> for Icon {
                 }
                 Ok(Self::Names(names))
             }
+            "file-descriptor" => {
+                let fd = data.downcast_ref::<zvariant::Fd>().map_err(|_| {
+                    de::Error::custom("Couldn't deserialize Icon of type 'file-descriptor'")
+                })?;
+                let fd = fd.as_fd().try_clone_to_owned().map_err(de::Error::custom)?;
+                Ok(Self::FileDescriptor(fd))
+            }
             _ => Err(de::Error::custom("Invalid Icon type")),
         }
     }
@@ -163,6 +192,10 @@ impl TryFrom<&OwnedValue> for Icon {
                 }
                 Ok(Self::Names(names))
             }
+            "file-descriptor" => {
+                let fd = fields[1].downcast_ref::<zvariant::Fd>()?;
+                Ok(Self::FileDescriptor(fd.as_fd().try_clone_to_owned()?))
+            }
             _ => Err(Error::ParseError("Invalid Icon type")),
         }
     }
@@ -218,5 +251,11 @@ mod test {
         let encoded = to_bytes(ctxt, &icon).unwrap();
         let decoded: Icon = encoded.deserialize().unwrap().0;
         assert_eq!(decoded, icon);
+
+        let file = std::fs::File::open("/dev/null").unwrap();
+        let icon = Icon::FileDescriptor(file.into());
+        let encoded = to_bytes(ctxt, &icon).unwrap();
+        let decoded: Icon = encoded.deserialize().unwrap().0;
+        assert!(matches!(decoded, Icon::FileDescriptor(_)));
     }
 }
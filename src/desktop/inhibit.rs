@@ -144,10 +144,19 @@ pub enum SessionState {
 #[doc(alias = "org.freedesktop.portal.Inhibit")]
 pub struct InhibitProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(InhibitProxy, "org.freedesktop.portal.Inhibit");
+
 impl<'a> InhibitProxy<'a> {
     /// Create a new instance of [`InhibitProxy`].
     pub async fn new() -> Result<InhibitProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Inhibit").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`InhibitProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<InhibitProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Inhibit").await?;
         Ok(Self(proxy))
     }
 
@@ -174,7 +183,8 @@ impl<'a> InhibitProxy<'a> {
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateMonitor", body)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(self.0.connection(), &options.session_handle_token)
+                .into_future(),
         )?;
         assert_eq!(proxy.path(), &monitor.response()?.session_handle.as_ref());
         Ok(proxy)
@@ -219,7 +229,9 @@ impl<'a> InhibitProxy<'a> {
     /// See also [`StateChanged`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Inhibit.html#org-freedesktop-portal-inhibit-statechanged).
     #[doc(alias = "StateChanged")]
     #[doc(alias = "XdpPortal::session-state-changed")]
-    pub async fn receive_state_changed(&self) -> Result<impl Stream<Item = InhibitState>, Error> {
+    pub async fn receive_state_changed(
+        &self,
+    ) -> Result<impl Stream<Item = InhibitState> + Unpin, Error> {
         self.0.signal("StateChanged").await
     }
 
@@ -297,10 +297,10 @@ use futures_util::{Stream, TryFutureExt};
 use serde::Deserialize;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{
-    self, DeserializeDict, ObjectPath, OwnedObjectPath, OwnedValue, SerializeDict, Type, Value,
+    self, DeserializeDict, ObjectPath, OwnedObjectPath, OwnedValue, SerializeDict, Type,
 };
 
-use super::{session::SessionPortal, HandleToken, Request, Session};
+use super::{session::SessionPortal, EmptyOptions, HandleToken, Request, Session};
 use crate::{proxy::Proxy, Error, WindowIdentifier};
 
 #[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Copy, Clone, Type)]
@@ -543,10 +543,19 @@ impl SetPointerBarriersResponse {
 #[doc(alias = "org.freedesktop.portal.InputCapture")]
 pub struct InputCapture<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(InputCapture, "org.freedesktop.portal.InputCapture");
+
 impl<'a> InputCapture<'a> {
     /// Create a new instance of [`InputCapture`].
     pub async fn new() -> Result<InputCapture<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.InputCapture").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`InputCapture`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<InputCapture<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.InputCapture").await?;
         Ok(Self(proxy))
     }
 
@@ -574,7 +583,8 @@ impl<'a> InputCapture<'a> {
                     (parent_window, &options)
                 )
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(self.0.connection(), &options.session_handle_token)
+                .into_future(),
         )?;
         let response = request.response()?;
         assert_eq!(proxy.path(), &response.session_handle.as_ref());
@@ -662,7 +672,7 @@ impl<'a> InputCapture<'a> {
     #[doc(alias = "ConnectToEIS")]
     pub async fn connect_to_eis(&self, session: &Session<'_, Self>) -> Result<OwnedFd, Error> {
         // `ConnectToEIS` doesn't take any options for now
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         let fd = self
             .0
             .call::<zvariant::OwnedFd>("ConnectToEIS", &(session, options))
@@ -677,7 +687,7 @@ impl<'a> InputCapture<'a> {
     ///
     /// See also [`Disabled`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-disabled).
     #[doc(alias = "Disabled")]
-    pub async fn receive_disabled(&self) -> Result<impl Stream<Item = Disabled>, Error> {
+    pub async fn receive_disabled(&self) -> Result<impl Stream<Item = Disabled> + Unpin, Error> {
         self.0.signal("Disabled").await
     }
 
@@ -688,7 +698,7 @@ impl<'a> InputCapture<'a> {
     ///
     /// See also [`Activated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-activated).
     #[doc(alias = "Activated")]
-    pub async fn receive_activated(&self) -> Result<impl Stream<Item = Activated>, Error> {
+    pub async fn receive_activated(&self) -> Result<impl Stream<Item = Activated> + Unpin, Error> {
         self.0.signal("Activated").await
     }
 
@@ -699,7 +709,9 @@ impl<'a> InputCapture<'a> {
     ///
     /// See also [`Deactivated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-deactivated).
     #[doc(alias = "Deactivated")]
-    pub async fn receive_deactivated(&self) -> Result<impl Stream<Item = Deactivated>, Error> {
+    pub async fn receive_deactivated(
+        &self,
+    ) -> Result<impl Stream<Item = Deactivated> + Unpin, Error> {
         self.0.signal("Deactivated").await
     }
 
@@ -709,7 +721,9 @@ impl<'a> InputCapture<'a> {
     ///
     /// See also [`ZonesChanged`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.InputCapture.html#org-freedesktop-portal-inputcapture-zoneschanged).
     #[doc(alias = "ZonesChanged")]
-    pub async fn receive_zones_changed(&self) -> Result<impl Stream<Item = ZonesChanged>, Error> {
+    pub async fn receive_zones_changed(
+        &self,
+    ) -> Result<impl Stream<Item = ZonesChanged> + Unpin, Error> {
         self.0.signal("ZonesChanged").await
     }
 
@@ -2,7 +2,7 @@
 //!
 //! ```rust,no_run
 //! use ashpd::{
-//!     desktop::location::{Accuracy, LocationProxy},
+//!     desktop::location::{Accuracy, CreateSessionOptions, LocationProxy},
 //!     WindowIdentifier,
 //! };
 //! use futures_util::{FutureExt, StreamExt};
@@ -11,7 +11,11 @@
 //!     let proxy = LocationProxy::new().await?;
 //!     let identifier = WindowIdentifier::default();
 //!     let session = proxy
-//!         .create_session(None, None, Some(Accuracy::Street))
+//!         .create_session_with_options(
+//!             CreateSessionOptions::default()
+//!                 .distance_threshold(100)
+//!                 .accuracy(Accuracy::Street),
+//!         )
 //!         .await?;
 //!     let mut stream = proxy.receive_location_updated().await?;
 //!     let (_, location) = futures_util::join!(
@@ -31,7 +35,7 @@
 use std::fmt::Debug;
 
 use futures_util::{Stream, TryFutureExt};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_repr::Serialize_repr;
 use zbus::zvariant::{DeserializeDict, ObjectPath, OwnedObjectPath, SerializeDict, Type};
 
@@ -66,9 +70,9 @@ pub enum Accuracy {
 }
 
 #[derive(SerializeDict, Type, Debug, Default)]
-/// Specified options for a [`LocationProxy::create_session`] request.
+/// Options to pass to [`LocationProxy::create_session_with_options`].
 #[zvariant(signature = "dict")]
-struct CreateSessionOptions {
+pub struct CreateSessionOptions {
     /// A string that will be used as the last element of the session handle.
     session_handle_token: HandleToken,
     /// Distance threshold in meters. Default is 0.
@@ -81,6 +85,31 @@ struct CreateSessionOptions {
     accuracy: Option<Accuracy>,
 }
 
+impl CreateSessionOptions {
+    /// Sets the distance in meters the location has to change by before it is
+    /// updated, default to `0`.
+    #[must_use]
+    pub fn distance_threshold(mut self, distance_threshold: impl Into<Option<u32>>) -> Self {
+        self.distance_threshold = distance_threshold.into();
+        self
+    }
+
+    /// Sets the time in seconds that has to pass before the location is
+    /// updated, default to `0`.
+    #[must_use]
+    pub fn time_threshold(mut self, time_threshold: impl Into<Option<u32>>) -> Self {
+        self.time_threshold = time_threshold.into();
+        self
+    }
+
+    /// Sets the location accuracy, default to [`Accuracy::Exact`].
+    #[must_use]
+    pub fn accuracy(mut self, accuracy: impl Into<Option<Accuracy>>) -> Self {
+        self.accuracy = accuracy.into();
+        self
+    }
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`LocationProxy::start`] request.
 #[zvariant(signature = "dict")]
@@ -89,7 +118,7 @@ struct SessionStartOptions {
     handle_token: HandleToken,
 }
 
-#[derive(Deserialize, Type)]
+#[derive(Serialize, Deserialize, Type)]
 /// The response received on a `location_updated` signal.
 pub struct Location(OwnedObjectPath, LocationInner);
 
@@ -201,10 +230,19 @@ struct LocationInner {
 #[doc(alias = "org.freedesktop.portal.Location")]
 pub struct LocationProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(LocationProxy, "org.freedesktop.portal.Location");
+
 impl<'a> LocationProxy<'a> {
     /// Create a new instance of [`LocationProxy`].
     pub async fn new() -> Result<LocationProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Location").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`LocationProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<LocationProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Location").await?;
         Ok(Self(proxy))
     }
 
@@ -215,7 +253,9 @@ impl<'a> LocationProxy<'a> {
     /// See also [`LocationUpdated`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Location.html#org-freedesktop-portal-location-locationupdated).
     #[doc(alias = "LocationUpdated")]
     #[doc(alias = "XdpPortal::location-updated")]
-    pub async fn receive_location_updated(&self) -> Result<impl Stream<Item = Location>, Error> {
+    pub async fn receive_location_updated(
+        &self,
+    ) -> Result<impl Stream<Item = Location> + Unpin, Error> {
         self.0.signal("LocationUpdated").await
     }
 
@@ -239,17 +279,29 @@ impl<'a> LocationProxy<'a> {
         time_threshold: Option<u32>,
         accuracy: Option<Accuracy>,
     ) -> Result<Session<'a, Self>, Error> {
-        let options = CreateSessionOptions {
-            distance_threshold,
-            time_threshold,
-            accuracy,
-            ..Default::default()
-        };
+        let options = CreateSessionOptions::default()
+            .distance_threshold(distance_threshold)
+            .time_threshold(time_threshold)
+            .accuracy(accuracy);
+        self.create_session_with_options(options).await
+    }
+
+    /// Create a location session with the thresholds and accuracy set on
+    /// `options`.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`CreateSession`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Location.html#org-freedesktop-portal-location-createsession).
+    pub async fn create_session_with_options(
+        &self,
+        options: CreateSessionOptions,
+    ) -> Result<Session<'a, Self>, Error> {
         let (path, proxy) = futures_util::try_join!(
             self.0
                 .call::<OwnedObjectPath>("CreateSession", &(options))
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(self.0.connection(), &options.session_handle_token)
+                .into_future(),
         )?;
         assert_eq!(proxy.path(), &path.into_inner());
         Ok(proxy)
@@ -32,10 +32,19 @@ use crate::{proxy::Proxy, Error};
 #[doc(alias = "org.freedesktop.portal.MemoryMonitor")]
 pub struct MemoryMonitor<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(MemoryMonitor, "org.freedesktop.portal.MemoryMonitor");
+
 impl<'a> MemoryMonitor<'a> {
     /// Create a new instance of [`MemoryMonitor`].
     pub async fn new() -> Result<MemoryMonitor<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.MemoryMonitor").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`MemoryMonitor`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<MemoryMonitor<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.MemoryMonitor").await?;
         Ok(Self(proxy))
     }
 
@@ -43,11 +52,16 @@ impl<'a> MemoryMonitor<'a> {
     /// with 0 being the lowest level of memory availability warning, and 255
     /// being the highest.
     ///
+    /// The interface has no property to watch, the warnings are only pushed
+    /// through this signal.
+    ///
     /// # Specifications
     ///
     /// See also [`LowMemoryWarning`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.MemoryMonitor.html#org-freedesktop-portal-memorymonitor-lowmemorywarning).
     #[doc(alias = "LowMemoryWarning")]
-    pub async fn receive_low_memory_warning(&self) -> Result<impl Stream<Item = i32>, Error> {
+    pub async fn receive_low_memory_warning(
+        &self,
+    ) -> Result<impl Stream<Item = i32> + Unpin, Error> {
         self.0.signal("LowMemoryWarning").await
     }
 }
@@ -3,11 +3,13 @@ pub(crate) mod request;
 mod session;
 pub(crate) use self::handle_token::HandleToken;
 pub use self::{
-    request::{Request, Response, ResponseError, ResponseType},
+    request::{Request, Response, ResponseError, ResponseResults, ResponseType},
     session::Session,
 };
 mod color;
 pub use color::Color;
+mod empty_options;
+pub(crate) use empty_options::EmptyOptions;
 mod icon;
 pub use icon::Icon;
 
@@ -47,6 +49,7 @@ pub mod proxy_resolver;
 pub mod realtime;
 /// Start a remote desktop session and interact with it.
 pub mod remote_desktop;
+pub mod restore_tokens;
 pub mod screencast;
 pub mod screenshot;
 /// Retrieve a per-application secret used to encrypt confidential data inside
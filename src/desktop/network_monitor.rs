@@ -3,6 +3,7 @@
 //!
 //! ```rust,no_run
 //! use ashpd::desktop::network_monitor::NetworkMonitor;
+//! use futures_util::StreamExt;
 //!
 //! async fn run() -> ashpd::Result<()> {
 //!     let proxy = NetworkMonitor::new().await?;
@@ -13,13 +14,18 @@
 //!     println!("{}", proxy.is_metered().await?);
 //!     println!("{:#?}", proxy.status().await?);
 //!
+//!     let mut changes = proxy.receive_status_changed().await?;
+//!     while let Some(status) = changes.next().await {
+//!         println!("{:#?}", status?.connectivity());
+//!     }
+//!
 //!     Ok(())
 //! }
 //! ```
 
 use std::fmt;
 
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt};
 use serde_repr::Deserialize_repr;
 use zbus::zvariant::{DeserializeDict, Type};
 
@@ -59,12 +65,23 @@ impl NetworkStatus {
 #[derive(Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
 #[repr(u32)]
 /// Host's network activity
+///
+/// Unlike [`NetworkMonitor::is_available`], it tells apart a host that is
+/// stuck behind a captive portal from one that can really reach the internet.
 pub enum Connectivity {
     /// The host is not configured with a route to the internet.
+    ///
+    /// Only the local network, if any, can be reached.
     Local = 1,
     /// The host is connected to a network, but can't reach the full internet.
+    ///
+    /// Some hosts might still be reachable, which can be checked with
+    /// [`NetworkMonitor::can_reach`].
     Limited = 2,
     /// The host is behind a captive portal and cannot reach the full internet.
+    ///
+    /// The user usually has to log in, or to accept some terms, with a web
+    /// browser first.
     CaptivePortal = 3,
     /// The host connected to a network, and can reach the full internet.
     FullNetwork = 4,
@@ -93,10 +110,21 @@ impl fmt::Display for Connectivity {
 #[doc(alias = "org.freedesktop.portal.NetworkMonitor")]
 pub struct NetworkMonitor<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(NetworkMonitor, "org.freedesktop.portal.NetworkMonitor");
+
 impl<'a> NetworkMonitor<'a> {
     /// Create a new instance of [`NetworkMonitor`].
     pub async fn new() -> Result<NetworkMonitor<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.NetworkMonitor").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`NetworkMonitor`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<NetworkMonitor<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.NetworkMonitor").await?;
         Ok(Self(proxy))
     }
 
@@ -109,7 +137,7 @@ impl<'a> NetworkMonitor<'a> {
     ///
     /// # Required version
     ///
-    /// The method requires the 3nd version implementation of the portal and
+    /// The method requires the 3rd version implementation of the portal and
     /// would fail with [`Error::RequiresVersion`] otherwise.
     ///
     /// # Specifications
@@ -178,7 +206,7 @@ impl<'a> NetworkMonitor<'a> {
     ///
     /// # Required version
     ///
-    /// The method requires the 3nd version implementation of the portal and
+    /// The method requires the 3rd version implementation of the portal and
     /// would fail with [`Error::RequiresVersion`] otherwise.
     ///
     /// # Specifications
@@ -195,9 +223,49 @@ impl<'a> NetworkMonitor<'a> {
     /// # Specifications
     ///
     /// See also [`changed`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.NetworkMonitor.html#org-freedesktop-portal-networkmonitor-changed).
-    pub async fn receive_changed(&self) -> Result<impl Stream<Item = ()>, Error> {
+    pub async fn receive_changed(&self) -> Result<impl Stream<Item = ()> + Unpin, Error> {
         self.0.signal("changed").await
     }
+
+    /// Emitted with the current network status, and again every time the
+    /// network configuration changes.
+    ///
+    /// The status is retrieved with [`status()`][`NetworkMonitor::status`],
+    /// or with the three separate calls on the 2nd version of the portal. The
+    /// stream yields the errors of those calls, and goes on with the next
+    /// change.
+    ///
+    /// # Required version
+    ///
+    /// The method requires the 2nd version implementation of the portal and
+    /// would fail with [`Error::RequiresVersion`] otherwise.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`changed`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.NetworkMonitor.html#org-freedesktop-portal-networkmonitor-changed).
+    pub async fn receive_status_changed(
+        &self,
+    ) -> Result<impl Stream<Item = Result<NetworkStatus, Error>> + Unpin + '_, Error> {
+        if self.version() < 2 {
+            return Err(Error::RequiresVersion(2, self.version()));
+        }
+        let changes = self.receive_changed().await?;
+        let stream = futures_util::stream::once(futures_util::future::ready(()))
+            .chain(changes)
+            .then(move |()| self.current_status());
+        Ok(Box::pin(stream))
+    }
+
+    async fn current_status(&self) -> Result<NetworkStatus, Error> {
+        if self.version() >= 3 {
+            return self.status().await;
+        }
+        Ok(NetworkStatus {
+            available: self.is_available().await?,
+            metered: self.is_metered().await?,
+            connectivity: self.connectivity().await?,
+        })
+    }
 }
 
 impl<'a> std::ops::Deref for NetworkMonitor<'a> {
@@ -207,3 +275,27 @@ impl<'a> std::ops::Deref for NetworkMonitor<'a> {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zbus::zvariant::{serialized::Context, to_bytes, Endian};
+
+    use super::*;
+
+    #[test]
+    fn connectivity_values() {
+        let ctxt = Context::new_dbus(Endian::Little, 0);
+        for (value, connectivity) in [
+            (1u32, Connectivity::Local),
+            (2, Connectivity::Limited),
+            (3, Connectivity::CaptivePortal),
+            (4, Connectivity::FullNetwork),
+        ] {
+            let encoded = to_bytes(ctxt, &value).unwrap();
+            assert_eq!(
+                encoded.deserialize::<Connectivity>().unwrap().0,
+                connectivity
+            );
+        }
+    }
+}
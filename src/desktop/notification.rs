@@ -50,9 +50,16 @@
 //! }
 //! ```
 
+#[cfg(feature = "notification_fallback")]
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 use std::{fmt, str::FromStr};
 
 use futures_util::Stream;
+#[cfg(feature = "notification_fallback")]
+use futures_util::StreamExt;
 use serde::{self, Deserialize, Serialize};
 use zbus::zvariant::{OwnedValue, SerializeDict, Type, Value};
 
@@ -61,7 +68,7 @@ use crate::{proxy::Proxy, Error};
 
 #[cfg_attr(feature = "glib", derive(glib::Enum))]
 #[cfg_attr(feature = "glib", enum_type(name = "AshpdPriority"))]
-#[derive(Debug, Copy, Clone, Serialize, PartialEq, Eq, Type)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
 #[zvariant(signature = "s")]
 #[serde(rename_all = "lowercase")]
 /// The notification priority
@@ -144,6 +151,8 @@ pub struct Notification {
     default_action_target: Option<OwnedValue>,
     /// Array of buttons to add to the notification.
     buttons: Option<Vec<Button>>,
+    /// The type of event the notification is about.
+    category: Option<String>,
 }
 
 impl Notification {
@@ -161,6 +170,7 @@ impl Notification {
             default_action: None,
             default_action_target: None,
             buttons: None,
+            category: None,
         }
     }
 
@@ -215,6 +225,24 @@ impl Notification {
         };
         self
     }
+
+    /// Sets the type of event the notification is about, used by the desktop
+    /// to pick how it is presented, a sound for example.
+    ///
+    /// The well-known categories are `im.received`, `alarm.ringing`,
+    /// `call.incoming`, `call.ongoing`, `call.missed`,
+    /// `weather.warning.extreme`, `cellbroadcast.danger.extreme`,
+    /// `cellbroadcast.danger.severe`, `cellbroadcast.amber-alert`,
+    /// `cellbroadcast.test`, `os.battery.low` and `browser.web-notification`.
+    /// Vendor specific categories start with `x-vendor.`.
+    ///
+    /// The hint is ignored by the portals older than version 2 of the
+    /// interface.
+    #[must_use]
+    pub fn category<'a>(mut self, category: impl Into<Option<&'a str>>) -> Self {
+        self.category = category.into().map(ToOwned::to_owned);
+        self
+    }
 }
 
 #[derive(SerializeDict, Type, Debug)]
@@ -297,10 +325,21 @@ impl Action {
 #[doc(alias = "org.freedesktop.portal.Notification")]
 pub struct NotificationProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(NotificationProxy, "org.freedesktop.portal.Notification");
+
 impl<'a> NotificationProxy<'a> {
     /// Create a new instance of [`NotificationProxy`].
     pub async fn new() -> Result<NotificationProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Notification").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`NotificationProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<NotificationProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Notification").await?;
         Ok(Self(proxy))
     }
 
@@ -311,7 +350,9 @@ impl<'a> NotificationProxy<'a> {
     /// See also [`ActionInvoked`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Notification.html#org-freedesktop-portal-notification-actioninvoked).
     #[doc(alias = "ActionInvoked")]
     #[doc(alias = "XdpPortal::notification-action-invoked")]
-    pub async fn receive_action_invoked(&self) -> Result<impl Stream<Item = Action>, Error> {
+    pub async fn receive_action_invoked(
+        &self,
+    ) -> Result<impl Stream<Item = Action> + Unpin, Error> {
         self.0.signal("ActionInvoked").await
     }
 
@@ -339,6 +380,33 @@ impl<'a> NotificationProxy<'a> {
         self.0.call("AddNotification", &(id, notification)).await
     }
 
+    /// Sends a notification and returns a handle to update or withdraw it
+    /// later.
+    ///
+    /// The handle holds on to `id`, which stays stable across the updates: the
+    /// desktop replaces the notification in place instead of showing a new
+    /// one.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Application-provided ID for this notification.
+    /// * `notification` - The notification.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`AddNotification`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Notification.html#org-freedesktop-portal-notification-addnotification).
+    pub async fn send(
+        &self,
+        id: &str,
+        notification: Notification,
+    ) -> Result<NotificationHandle<'_, 'a>, Error> {
+        self.add_notification(id, notification).await?;
+        Ok(NotificationHandle {
+            proxy: self,
+            id: id.to_owned(),
+        })
+    }
+
     /// Withdraws a notification.
     ///
     /// # Arguments
@@ -355,6 +423,32 @@ impl<'a> NotificationProxy<'a> {
     }
 }
 
+/// A notification sent with [`NotificationProxy::send`].
+#[derive(Debug)]
+pub struct NotificationHandle<'p, 'a> {
+    proxy: &'p NotificationProxy<'a>,
+    id: String,
+}
+
+impl<'p, 'a> NotificationHandle<'p, 'a> {
+    /// The application-provided ID of the notification.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Replaces the notification with `notification`.
+    ///
+    /// The notification is sent again if it was dismissed in the meantime.
+    pub async fn update(&self, notification: Notification) -> Result<(), Error> {
+        self.proxy.add_notification(&self.id, notification).await
+    }
+
+    /// Withdraws the notification.
+    pub async fn withdraw(self) -> Result<(), Error> {
+        self.proxy.remove_notification(&self.id).await
+    }
+}
+
 impl<'a> std::ops::Deref for NotificationProxy<'a> {
     type Target = zbus::Proxy<'a>;
 
@@ -362,3 +456,306 @@ impl<'a> std::ops::Deref for NotificationProxy<'a> {
         &self.0
     }
 }
+
+#[cfg(feature = "notification_fallback")]
+#[derive(Debug)]
+enum Sender<'a> {
+    Portal(NotificationProxy<'a>),
+    Freedesktop {
+        proxy: Proxy<'a>,
+        sent: Arc<Mutex<HashMap<String, Sent>>>,
+    },
+}
+
+/// A notification sent over `org.freedesktop.Notifications`.
+#[cfg(feature = "notification_fallback")]
+#[derive(Debug)]
+struct Sent {
+    /// The ID assigned by the notification server.
+    id: u32,
+    /// The action names and targets, keyed by the action keys.
+    actions: HashMap<String, (String, Option<OwnedValue>)>,
+}
+
+/// Sends the notifications through the notification portal, or through the
+/// [`org.freedesktop.Notifications`](https://specifications.freedesktop.org/notification-spec/latest/)
+/// interface of the notification server when the portal is not available, for
+/// applications that run both inside and outside of Flatpak.
+///
+/// The same [`Notification`] is used with both, but not all of it can be
+/// translated for the notification server:
+/// - The icons can only be referred to by name or file URI, the bytes and file
+///   descriptors are dropped.
+/// - The priorities are mapped to the low, normal and critical urgencies, the
+///   high priority being normal.
+/// - The notifications don't outlast the application, their actions are only
+///   emitted by [`NotificationSender::receive_action_invoked`] while it runs.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::notification::{Button, Notification, NotificationSender};
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let sender = NotificationSender::new().await?;
+///     sender
+///         .add_notification(
+///             "download",
+///             Notification::new("Download complete")
+///                 .default_action("app.open-download")
+///                 .button(Button::new("Show in Folder", "app.show-download")),
+///         )
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "notification_fallback")]
+#[cfg_attr(docsrs, doc(cfg(feature = "notification_fallback")))]
+#[derive(Debug)]
+pub struct NotificationSender<'a>(Sender<'a>);
+
+#[cfg(feature = "notification_fallback")]
+impl<'a> NotificationSender<'a> {
+    /// Create a new instance of [`NotificationSender`].
+    pub async fn new() -> Result<NotificationSender<'a>, Error> {
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`NotificationSender`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<NotificationSender<'a>, Error> {
+        match NotificationProxy::with_connection(connection.clone()).await {
+            Ok(proxy) => Ok(Self(Sender::Portal(proxy))),
+            Err(Error::PortalNotFound(_)) => {
+                let proxy = Proxy::new(
+                    connection,
+                    "org.freedesktop.Notifications",
+                    "/org/freedesktop/Notifications",
+                    "org.freedesktop.Notifications",
+                )
+                .await?;
+                Ok(Self(Sender::Freedesktop {
+                    proxy,
+                    sent: Default::default(),
+                }))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Whether the notifications are sent through the notification portal.
+    pub fn is_portal(&self) -> bool {
+        matches!(self.0, Sender::Portal(_))
+    }
+
+    /// Signal emitted when a particular action is invoked.
+    ///
+    /// See also [`NotificationProxy::receive_action_invoked`].
+    pub async fn receive_action_invoked(
+        &self,
+    ) -> Result<impl Stream<Item = Action> + Unpin, Error> {
+        match &self.0 {
+            Sender::Portal(proxy) => Ok(futures_util::future::Either::Left(
+                proxy.receive_action_invoked().await?,
+            )),
+            Sender::Freedesktop { proxy, sent } => {
+                let sent = Arc::clone(sent);
+                let actions = proxy
+                    .signal::<(u32, String)>("ActionInvoked")
+                    .await?
+                    .filter_map(move |(server_id, key)| {
+                        let sent = sent.lock().unwrap();
+                        let action = sent
+                            .iter()
+                            .find(|(_, notification)| notification.id == server_id)
+                            .and_then(|(id, notification)| {
+                                let (name, target) = notification.actions.get(&key)?;
+                                let parameter = target
+                                    .as_ref()
+                                    .and_then(|target| target.try_clone().ok())
+                                    .into_iter()
+                                    .collect();
+                                Some(Action(id.clone(), name.clone(), parameter))
+                            });
+                        futures_util::future::ready(action)
+                    });
+                Ok(futures_util::future::Either::Right(actions))
+            }
+        }
+    }
+
+    /// Sends a notification, replacing the one previously sent with the same
+    /// ID.
+    ///
+    /// See also [`NotificationProxy::add_notification`].
+    pub async fn add_notification(
+        &self,
+        id: &str,
+        notification: Notification,
+    ) -> Result<(), Error> {
+        match &self.0 {
+            Sender::Portal(proxy) => proxy.add_notification(id, notification).await,
+            Sender::Freedesktop { proxy, sent } => {
+                let replaces_id = sent.lock().unwrap().get(id).map_or(0, |sent| sent.id);
+                let notification = FreedesktopNotification::from(notification);
+                let server_id = proxy
+                    .call::<u32>(
+                        "Notify",
+                        &(
+                            "",
+                            replaces_id,
+                            &notification.app_icon,
+                            &notification.summary,
+                            &notification.body,
+                            &notification.actions,
+                            &notification.hints,
+                            -1i32,
+                        ),
+                    )
+                    .await?;
+                sent.lock().unwrap().insert(
+                    id.to_owned(),
+                    Sent {
+                        id: server_id,
+                        actions: notification.targets,
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Withdraws a notification.
+    ///
+    /// See also [`NotificationProxy::remove_notification`].
+    pub async fn remove_notification(&self, id: &str) -> Result<(), Error> {
+        match &self.0 {
+            Sender::Portal(proxy) => proxy.remove_notification(id).await,
+            Sender::Freedesktop { proxy, sent } => {
+                let sent = sent.lock().unwrap().remove(id);
+                match sent {
+                    Some(sent) => proxy.call("CloseNotification", &(sent.id)).await,
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// The arguments of a `org.freedesktop.Notifications.Notify` call.
+#[cfg(feature = "notification_fallback")]
+#[derive(Debug)]
+struct FreedesktopNotification {
+    app_icon: String,
+    summary: String,
+    body: String,
+    /// The action keys, each followed by its label.
+    actions: Vec<String>,
+    hints: HashMap<&'static str, OwnedValue>,
+    /// The action names and targets, keyed by the action keys.
+    targets: HashMap<String, (String, Option<OwnedValue>)>,
+}
+
+#[cfg(feature = "notification_fallback")]
+impl From<Notification> for FreedesktopNotification {
+    fn from(notification: Notification) -> Self {
+        let app_icon = match notification.icon {
+            Some(Icon::Names(names)) => names.into_iter().next().unwrap_or_default(),
+            Some(Icon::Uri(uri)) if uri.scheme() == "file" => uri.to_string(),
+            _ => String::new(),
+        };
+
+        let mut hints = HashMap::new();
+        if let Some(priority) = notification.priority {
+            let urgency: u8 = match priority {
+                Priority::Low => 0,
+                Priority::Normal | Priority::High => 1,
+                Priority::Urgent => 2,
+            };
+            hints.insert("urgency", OwnedValue::from(urgency));
+        }
+        if let Some(category) = notification.category {
+            hints.insert("category", Value::from(category).try_into().unwrap());
+        }
+
+        let mut actions = Vec::new();
+        let mut targets = HashMap::new();
+        if let Some(default_action) = notification.default_action {
+            // The notification server invokes the `default` action when the
+            // notification is clicked.
+            actions.extend(["default".to_owned(), String::new()]);
+            targets.insert(
+                "default".to_owned(),
+                (default_action, notification.default_action_target),
+            );
+        }
+        for (index, button) in notification.buttons.into_iter().flatten().enumerate() {
+            let key = index.to_string();
+            actions.extend([key.clone(), button.label]);
+            targets.insert(key, (button.action, button.target));
+        }
+
+        Self {
+            app_icon,
+            summary: notification.title,
+            body: notification.body.unwrap_or_default(),
+            actions,
+            hints,
+            targets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::{serialized::Context, to_bytes, LE};
+
+    use super::*;
+
+    #[test]
+    fn notification_category() {
+        let ctxt = Context::new_dbus(LE, 0);
+        let notification = Notification::new("Alarm").category("alarm.ringing");
+        let encoded = to_bytes(ctxt, &notification).unwrap();
+        let (dict, _) = encoded
+            .deserialize::<HashMap<String, OwnedValue>>()
+            .unwrap();
+        assert_eq!(
+            dict.get("category")
+                .map(|v| v.downcast_ref::<&str>().unwrap()),
+            Some("alarm.ringing")
+        );
+        assert!(!dict.contains_key("body"));
+    }
+
+    #[cfg(feature = "notification_fallback")]
+    #[test]
+    fn freedesktop_notification() {
+        let notification = FreedesktopNotification::from(
+            Notification::new("Download complete")
+                .icon(Icon::with_names(["folder-download"]))
+                .priority(Priority::Urgent)
+                .default_action("app.open")
+                .default_action_target("file.pdf")
+                .button(Button::new("Show in Folder", "app.show")),
+        );
+        assert_eq!(notification.app_icon, "folder-download");
+        assert_eq!(notification.summary, "Download complete");
+        assert_eq!(notification.body, "");
+        assert_eq!(notification.actions, ["default", "", "0", "Show in Folder"]);
+        assert_eq!(
+            notification.hints.get("urgency"),
+            Some(&OwnedValue::from(2u8))
+        );
+        let (name, target) = &notification.targets["default"];
+        assert_eq!(name, "app.open");
+        assert_eq!(
+            target.as_ref().map(|t| t.downcast_ref::<&str>().unwrap()),
+            Some("file.pdf")
+        );
+        assert_eq!(notification.targets["0"].0, "app.show");
+    }
+}
@@ -21,6 +21,20 @@
 //! }
 //! ```
 //!
+//! Or by passing the path of the file
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::open_uri::OpenFileRequest;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     OpenFileRequest::default()
+//!         .ask(true)
+//!         .send_path("/home/bilelmoussaoui/adwaita-day.jpg")
+//!         .await?;
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ## Open a file from a URI
 //!
 //!
@@ -51,7 +65,10 @@
 //! }
 //! ```
 
-use std::os::fd::BorrowedFd;
+use std::{
+    os::fd::{AsFd, BorrowedFd},
+    path::Path,
+};
 
 use url::Url;
 use zbus::zvariant::{Fd, SerializeDict, Type};
@@ -70,7 +87,7 @@ struct OpenDirOptions {
 #[zvariant(signature = "dict")]
 struct OpenFileOptions {
     handle_token: HandleToken,
-    writeable: Option<bool>,
+    writable: Option<bool>,
     ask: Option<bool>,
     activation_token: Option<ActivationToken>,
 }
@@ -78,9 +95,11 @@ struct OpenFileOptions {
 #[derive(Debug)]
 struct OpenURIProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(request OpenFileRequest, OpenDirectoryRequest => "org.freedesktop.portal.OpenURI");
+
 impl<'a> OpenURIProxy<'a> {
-    pub async fn new() -> Result<OpenURIProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.OpenURI").await?;
+    pub async fn with_connection(connection: zbus::Connection) -> Result<OpenURIProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.OpenURI").await?;
         Ok(Self(proxy))
     }
 
@@ -146,6 +165,7 @@ impl<'a> std::ops::Deref for OpenURIProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct OpenFileRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     options: OpenFileOptions,
 }
 
@@ -158,12 +178,31 @@ impl OpenFileRequest {
     }
 
     #[must_use]
-    /// Whether the file should be writeable or not.
-    pub fn writeable(mut self, writeable: impl Into<Option<bool>>) -> Self {
-        self.options.writeable = writeable.into();
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
+    #[must_use]
+    /// Whether the chosen application can write to the file or not.
+    ///
+    /// Only has an effect on files exported in the document portal, opened in
+    /// a sandboxed application.
+    pub fn writable(mut self, writable: impl Into<Option<bool>>) -> Self {
+        self.options.writable = writable.into();
         self
     }
 
+    #[must_use]
+    #[deprecated = "Use `OpenFileRequest::writable` instead"]
+    /// Whether the file should be writeable or not.
+    pub fn writeable(self, writeable: impl Into<Option<bool>>) -> Self {
+        self.writable(writeable)
+    }
+
     #[must_use]
     /// Whether to always ask the user which application to use or not.
     pub fn ask(mut self, ask: impl Into<Option<bool>>) -> Self {
@@ -182,16 +221,55 @@ impl OpenFileRequest {
     }
 
     /// Send the request for a file.
+    ///
+    /// The request [`response()`][`Request::response`] fails with
+    /// [`ResponseError::Cancelled`](crate::desktop::ResponseError::Cancelled)
+    /// if the user dismissed the application chooser.
     pub async fn send_file(self, file: &BorrowedFd<'_>) -> Result<Request<()>, Error> {
-        let proxy = OpenURIProxy::new().await?;
+        let proxy =
+            OpenURIProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy.open_file(&self.identifier, file, self.options).await
     }
 
+    /// Send the request for the file at `path`.
+    ///
+    /// The file is opened with `O_PATH`, the portal only needs the file
+    /// descriptor to identify it.
+    pub async fn send_path(self, path: impl AsRef<Path>) -> Result<Request<()>, Error> {
+        let fd = crate::helpers::open_path(path.as_ref())?;
+        self.send_file(&fd.as_fd()).await
+    }
+
     /// Send the request for a URI.
     pub async fn send_uri(self, uri: &Url) -> Result<Request<()>, Error> {
-        let proxy = OpenURIProxy::new().await?;
+        let proxy =
+            OpenURIProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy.open_uri(&self.identifier, uri, self.options).await
     }
+
+    #[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+    /// Send the request for a [`gio::File`](gtk4::gio::File).
+    ///
+    /// The local files are sent with [`OpenFileRequest::send_path`], the
+    /// others with [`OpenFileRequest::send_uri`].
+    pub async fn send_gio_file(
+        self,
+        file: &impl gtk4::glib::prelude::IsA<gtk4::gio::File>,
+    ) -> Result<Request<()>, Error> {
+        use gtk4::gio::prelude::FileExt;
+
+        let file = file.as_ref();
+        match file.path() {
+            Some(path) => self.send_path(path).await,
+            None => {
+                let uri = Url::parse(&file.uri()).map_err(|_| Error::ParseError("Invalid URI"))?;
+                self.send_uri(&uri).await
+            }
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -202,6 +280,7 @@ impl OpenFileRequest {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct OpenDirectoryRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     options: OpenDirOptions,
 }
 
@@ -213,6 +292,15 @@ impl OpenDirectoryRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Sets the token that can be used to activate the chosen application.
     #[must_use]
     pub fn activation_token(
@@ -225,9 +313,44 @@ impl OpenDirectoryRequest {
 
     /// Send the request.
     pub async fn send(self, directory: &BorrowedFd<'_>) -> Result<Request<()>, Error> {
-        let proxy = OpenURIProxy::new().await?;
+        let proxy =
+            OpenURIProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy
             .open_directory(&self.identifier, directory, self.options)
             .await
     }
+
+    /// Send the request for the directory at `path`.
+    ///
+    /// The directory is opened with `O_PATH`, the portal only needs the file
+    /// descriptor to identify it.
+    pub async fn send_path(self, path: impl AsRef<Path>) -> Result<Request<()>, Error> {
+        let fd = crate::helpers::open_path(path.as_ref())?;
+        self.send(&fd.as_fd()).await
+    }
+
+    #[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+    /// Send the request for a [`gio::File`](gtk4::gio::File).
+    ///
+    /// Only local directories can be opened.
+    pub async fn send_gio_file(
+        self,
+        directory: &impl gtk4::glib::prelude::IsA<gtk4::gio::File>,
+    ) -> Result<Request<()>, Error> {
+        use gtk4::gio::prelude::FileExt;
+
+        let path = directory.as_ref().path().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "Not a local directory")
+        })?;
+        self.send_path(path).await
+    }
+}
+
+#[doc(alias = "xdp_portal_open_uri")]
+/// A handy wrapper around [`OpenFileRequest::send_uri`] that waits for the
+/// user's response.
+pub async fn open(uri: &Url) -> Result<(), Error> {
+    OpenFileRequest::default().send_uri(uri).await?.response()
 }
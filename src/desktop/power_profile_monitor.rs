@@ -1,3 +1,5 @@
+use futures_util::Stream;
+
 use crate::{proxy::Proxy, Error};
 
 /// The interface provides information about the user-selected system-wide power
@@ -14,10 +16,25 @@ use crate::{proxy::Proxy, Error};
 #[doc(alias = "org.freedesktop.portal.PowerProfileMonitor")]
 pub struct PowerProfileMonitor<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(
+    PowerProfileMonitor,
+    "org.freedesktop.portal.PowerProfileMonitor"
+);
+
 impl<'a> PowerProfileMonitor<'a> {
     /// Create a new instance of [`PowerProfileMonitor`].
     pub async fn new() -> Result<PowerProfileMonitor<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.PowerProfileMonitor").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`PowerProfileMonitor`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<PowerProfileMonitor<'a>, Error> {
+        let proxy =
+            Proxy::new_desktop(connection, "org.freedesktop.portal.PowerProfileMonitor").await?;
         Ok(Self(proxy))
     }
 
@@ -30,6 +47,18 @@ impl<'a> PowerProfileMonitor<'a> {
     pub async fn is_enabled(&self) -> Result<bool, Error> {
         self.0.property("power-saver-enabled").await
     }
+
+    /// Emitted with the current state of the power saver, and every time it is
+    /// enabled or disabled.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`power-saver-enabled`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.PowerProfileMonitor.html#org-freedesktop-portal-powerprofilemonitor-power-saver-enabled)
+    pub async fn receive_is_enabled_changed(
+        &self,
+    ) -> Result<impl Stream<Item = bool> + Unpin + '_, Error> {
+        Ok(self.0.receive_property_changed("power-saver-enabled").await)
+    }
 }
 
 impl<'a> std::ops::Deref for PowerProfileMonitor<'a> {
@@ -38,8 +38,18 @@
 //! }
 //! ```
 
-use std::{fmt, os::fd::BorrowedFd, str::FromStr};
-
+use std::{
+    fmt,
+    fs::File,
+    io::{Seek, Write},
+    os::fd::{AsFd, AsRawFd, BorrowedFd},
+    str::FromStr,
+};
+
+use nix::{
+    fcntl::{fcntl, FcntlArg, SealFlag},
+    sys::memfd::{memfd_create, MemFdCreateFlag},
+};
 use serde::{Deserialize, Serialize};
 use zbus::zvariant::{DeserializeDict, Fd, SerializeDict, Type};
 
@@ -602,12 +612,14 @@ struct PrintOptions {
 
 impl PrintOptions {
     /// A token retrieved from [`PrintProxy::prepare_print`].
+    #[must_use]
     pub fn token(mut self, token: impl Into<Option<u32>>) -> Self {
         self.token = token.into();
         self
     }
 
     /// Sets whether the dialog should be a modal.
+    #[must_use]
     pub fn modal(mut self, modal: impl Into<Option<bool>>) -> Self {
         self.modal = modal.into();
         self
@@ -634,10 +646,19 @@ pub struct PreparePrint {
 #[doc(alias = "org.freedesktop.portal.Print")]
 pub struct PrintProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(PrintProxy, "org.freedesktop.portal.Print");
+
 impl<'a> PrintProxy<'a> {
     /// Create a new instance of [`PrintProxy`].
     pub async fn new() -> Result<PrintProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Print").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`PrintProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<PrintProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Print").await?;
         Ok(Self(proxy))
     }
 
@@ -719,6 +740,61 @@ impl<'a> PrintProxy<'a> {
             )
             .await
     }
+
+    /// Asks to print a document held in memory, a PDF rendered by the
+    /// application for example.
+    ///
+    /// The bytes are copied to a sealed memory file that is passed to
+    /// [`print()`][`PrintProxy::print`], so nothing is written to the file
+    /// system.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The application window identifier.
+    /// * `title` - The title for the print dialog.
+    /// * `bytes` - The content to print.
+    /// * `token` - A token returned by a call to
+    ///   [`prepare_print()`][`PrintProxy::prepare_print`].
+    /// * `modal` - Whether the dialog should be a modal.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Print`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Print.html#org-freedesktop-portal-print-print).
+    pub async fn print_bytes(
+        &self,
+        identifier: &WindowIdentifier,
+        title: &str,
+        bytes: &[u8],
+        token: Option<u32>,
+        modal: bool,
+    ) -> Result<Request<()>, Error> {
+        let file = sealed_file(title, bytes)?;
+        self.print(identifier, title, &file.as_fd(), token, modal)
+            .await
+    }
+}
+
+/// Creates a memory file holding `bytes` that can no longer be modified.
+fn sealed_file(name: &str, bytes: &[u8]) -> std::io::Result<File> {
+    // The name is only used for debugging, and must not contain a NUL byte.
+    let name = std::ffi::CString::new(name.replace('\0', "")).unwrap_or_default();
+    let fd = memfd_create(
+        &name,
+        MemFdCreateFlag::MFD_CLOEXEC | MemFdCreateFlag::MFD_ALLOW_SEALING,
+    )?;
+    let mut file = File::from(fd);
+    file.write_all(bytes)?;
+    file.rewind()?;
+    fcntl(
+        file.as_raw_fd(),
+        FcntlArg::F_ADD_SEALS(
+            SealFlag::F_SEAL_SHRINK
+                | SealFlag::F_SEAL_GROW
+                | SealFlag::F_SEAL_WRITE
+                | SealFlag::F_SEAL_SEAL,
+        ),
+    )?;
+    Ok(file)
 }
 
 impl<'a> std::ops::Deref for PrintProxy<'a> {
@@ -728,3 +804,19 @@ impl<'a> std::ops::Deref for PrintProxy<'a> {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn sealed_file_content() {
+        let mut file = sealed_file("document.pdf", b"%PDF-1.7").unwrap();
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"%PDF-1.7");
+        assert!(file.write_all(b"more").is_err());
+    }
+}
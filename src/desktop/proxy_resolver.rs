@@ -27,10 +27,19 @@ use crate::{proxy::Proxy, Error};
 #[doc(alias = "org.freedesktop.portal.ProxyResolver")]
 pub struct ProxyResolver<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(ProxyResolver, "org.freedesktop.portal.ProxyResolver");
+
 impl<'a> ProxyResolver<'a> {
     /// Create a new instance of [`ProxyResolver`].
     pub async fn new() -> Result<ProxyResolver<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.ProxyResolver").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`ProxyResolver`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<ProxyResolver<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.ProxyResolver").await?;
         Ok(Self(proxy))
     }
 
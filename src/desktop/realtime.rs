@@ -11,10 +11,19 @@ use crate::{proxy::Proxy, Error};
 #[doc(alias = "org.freedesktop.portal.Realtime")]
 pub struct Realtime<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(Realtime, "org.freedesktop.portal.Realtime");
+
 impl<'a> Realtime<'a> {
     /// Create a new instance of [`Realtime`].
     pub async fn new() -> Result<Realtime<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Realtime").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Realtime`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Realtime<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Realtime").await?;
         Ok(Self(proxy))
     }
 
@@ -3,7 +3,9 @@
 //! ```rust,no_run
 //! use ashpd::{
 //!     desktop::{
-//!         remote_desktop::{DeviceType, KeyState, RemoteDesktop},
+//!         remote_desktop::{
+//!             keysym_from_char, DeviceType, KeyState, PointerButton, RemoteDesktop,
+//!         },
 //!         PersistMode,
 //!     },
 //!     WindowIdentifier,
@@ -32,6 +34,19 @@
 //!         .notify_keyboard_keycode(&session, 13, KeyState::Pressed)
 //!         .await?;
 //!
+//!     proxy
+//!         .notify_pointer_button(&session, PointerButton::Left, KeyState::Pressed)
+//!         .await?;
+//!     proxy
+//!         .notify_pointer_button(&session, PointerButton::Left, KeyState::Released)
+//!         .await?;
+//!
+//!     if let Some(keysym) = keysym_from_char('a') {
+//!         proxy
+//!             .notify_keyboard_keysym(&session, keysym, KeyState::Pressed)
+//!             .await?;
+//!     }
+//!
 //!     Ok(())
 //! }
 //! ```
@@ -95,15 +110,16 @@
 //! [select_sources]: crate::desktop::screencast::Screencast::select_sources
 //! [create_session]: crate::desktop::remote_desktop::RemoteDesktop::create_session
 
-use std::{collections::HashMap, os::fd::OwnedFd};
+use std::os::fd::OwnedFd;
 
 use enumflags2::{bitflags, BitFlags};
 use futures_util::TryFutureExt;
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{self, DeserializeDict, SerializeDict, Type, Value};
+use zbus::zvariant::{self, DeserializeDict, SerializeDict, Type};
 
 use super::{
-    screencast::Stream, session::SessionPortal, HandleToken, PersistMode, Request, Session,
+    screencast::Stream, session::SessionPortal, EmptyOptions, HandleToken, PersistMode, Request,
+    Session,
 };
 use crate::{desktop::session::CreateSessionResponse, proxy::Proxy, Error, WindowIdentifier};
 
@@ -154,6 +170,268 @@ pub enum Axis {
     Horizontal = 1,
 }
 
+#[cfg_attr(feature = "glib", derive(glib::Enum))]
+#[cfg_attr(feature = "glib", enum_type(name = "AshpdPointerButton"))]
+#[derive(Serialize_repr, Deserialize_repr, PartialEq, Eq, Debug, Clone, Copy, Type)]
+#[repr(i32)]
+/// A pointer button, encoded according to the Linux evdev button codes.
+pub enum PointerButton {
+    #[doc(alias = "BTN_LEFT")]
+    /// The left button.
+    Left = 0x110,
+    #[doc(alias = "BTN_RIGHT")]
+    /// The right button.
+    Right = 0x111,
+    #[doc(alias = "BTN_MIDDLE")]
+    /// The middle button, usually the scroll wheel.
+    Middle = 0x112,
+    #[doc(alias = "BTN_SIDE")]
+    /// The side button.
+    Side = 0x113,
+    #[doc(alias = "BTN_EXTRA")]
+    /// The extra button.
+    Extra = 0x114,
+    #[doc(alias = "BTN_FORWARD")]
+    /// The forward button.
+    Forward = 0x115,
+    #[doc(alias = "BTN_BACK")]
+    /// The back button.
+    Back = 0x116,
+    #[doc(alias = "BTN_TASK")]
+    /// The task button.
+    Task = 0x117,
+}
+
+impl From<PointerButton> for i32 {
+    fn from(button: PointerButton) -> Self {
+        button as i32
+    }
+}
+
+/// Convert a character to the corresponding X11 keysym, to be used with
+/// [`RemoteDesktop::notify_keyboard_keysym`].
+///
+/// Printable Latin-1 characters map to their code point, other Unicode
+/// characters to their `0x01000000` offset keysym. `'\n'` and `'\r'` map to
+/// `Return`. Returns `None` for the control characters that have no keysym.
+#[doc(alias = "xkb_utf32_to_keysym")]
+pub fn keysym_from_char(c: char) -> Option<i32> {
+    let code = c as u32;
+    let keysym = match code {
+        0x08 => 0xff08,        // BackSpace
+        0x09 => 0xff09,        // Tab
+        0x0a | 0x0d => 0xff0d, // Return
+        0x1b => 0xff1b,        // Escape
+        0x7f => 0xffff,        // Delete
+        0x20..=0x7e | 0xa0..=0xff => code,
+        0x00..=0x1f | 0x80..=0x9f => return None,
+        _ => 0x0100_0000 | code,
+    };
+    Some(keysym as i32)
+}
+
+/// Look up the X11 keysym of a key name, like `Return` or `ssharp`, to be used
+/// with [`RemoteDesktop::notify_keyboard_keysym`].
+///
+/// Returns `None` if the name is not a known keysym name.
+#[cfg(feature = "xkbcommon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "xkbcommon")))]
+#[doc(alias = "xkb_keysym_from_name")]
+pub fn keysym_from_name(name: &str) -> Option<i32> {
+    if name.contains('\0') {
+        return None;
+    }
+    let keysym = xkbcommon::xkb::keysym_from_name(name, xkbcommon::xkb::KEYSYM_NO_FLAGS);
+    (keysym != xkbcommon::xkb::Keysym::NoSymbol).then_some(keysym.raw() as i32)
+}
+
+/// Look up the key producing `keysym` in a keymap, to be used with
+/// [`RemoteDesktop::notify_keyboard_keycode`].
+///
+/// # Returns
+///
+/// The evdev code of the first key producing the keysym in the first layout
+/// of `keymap` and its shift level. A level other than `0` means a modifier,
+/// like `Shift` for level `1`, has to be held down for the key to produce the
+/// keysym. Returns `None` if no key of that layout produces it.
+#[cfg(feature = "xkbcommon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "xkbcommon")))]
+pub fn keycode_from_keysym(keymap: &xkbcommon::xkb::Keymap, keysym: i32) -> Option<(i32, u32)> {
+    let keysym = xkbcommon::xkb::Keysym::new(keysym as u32);
+    (keymap.min_keycode().raw()..=keymap.max_keycode().raw())
+        .map(xkbcommon::xkb::Keycode::new)
+        .filter(|key| keymap.num_layouts_for_key(*key) > 0)
+        .find_map(|key| {
+            (0..keymap.num_levels_for_key(key, 0))
+                .find(|level| keymap.key_get_syms_by_level(key, 0, *level) == [keysym])
+                // The xkb keycodes are the evdev ones offset by 8.
+                .map(|level| (key.raw() as i32 - 8, level))
+        })
+}
+
+/// An input event of an [`InputBatch`].
+///
+/// Each variant corresponds to one of the `notify_*` methods of
+/// [`RemoteDesktop`], with the same arguments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    /// See [`RemoteDesktop::notify_pointer_motion`].
+    PointerMotion {
+        /// Relative movement on the x axis.
+        dx: f64,
+        /// Relative movement on the y axis.
+        dy: f64,
+    },
+    /// See [`RemoteDesktop::notify_pointer_motion_absolute`].
+    PointerMotionAbsolute {
+        /// PipeWire stream node the coordinates are relative to.
+        stream: u32,
+        /// Pointer motion x coordinate.
+        x: f64,
+        /// Pointer motion y coordinate.
+        y: f64,
+    },
+    /// See [`RemoteDesktop::notify_pointer_button`].
+    PointerButton {
+        /// The evdev code of the pointer button that was pressed or released,
+        /// see [`PointerButton`].
+        button: i32,
+        /// The new state of the button.
+        state: KeyState,
+    },
+    /// See [`RemoteDesktop::notify_pointer_axis`].
+    PointerAxis {
+        /// Relative axis movement on the x axis.
+        dx: f64,
+        /// Relative axis movement on the y axis.
+        dy: f64,
+        /// Whether it is the last axis event.
+        finish: bool,
+    },
+    /// See [`RemoteDesktop::notify_pointer_axis_discrete`].
+    PointerAxisDiscrete {
+        /// The axis that was scrolled.
+        axis: Axis,
+        /// The number of steps scrolled.
+        steps: i32,
+    },
+    /// See [`RemoteDesktop::notify_keyboard_keycode`].
+    KeyboardKeycode {
+        /// Keyboard code that was pressed or released.
+        keycode: i32,
+        /// The new state of the keyboard code.
+        state: KeyState,
+    },
+    /// See [`RemoteDesktop::notify_keyboard_keysym`].
+    KeyboardKeysym {
+        /// Keyboard symbol that was pressed or released.
+        keysym: i32,
+        /// The new state of the keyboard symbol.
+        state: KeyState,
+    },
+}
+
+/// Input events sent together with [`RemoteDesktop::notify_batch`], for
+/// example the events of a frame.
+///
+/// Consecutive events that can be merged are coalesced when pushed: relative
+/// pointer motions and unfinished axis movements are summed, absolute motions
+/// on the same stream replace each other and discrete steps on the same axis
+/// are added up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputBatch(Vec<InputEvent>);
+
+impl InputBatch {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an event to the batch, merging it with the last one if possible.
+    pub fn push(&mut self, event: InputEvent) {
+        use InputEvent::*;
+
+        let merged = match (self.0.last_mut(), event) {
+            (Some(PointerMotion { dx, dy }), PointerMotion { dx: x, dy: y }) => {
+                *dx += x;
+                *dy += y;
+                true
+            }
+            (
+                Some(PointerMotionAbsolute { stream, x, y }),
+                PointerMotionAbsolute {
+                    stream: new_stream,
+                    x: new_x,
+                    y: new_y,
+                },
+            ) if *stream == new_stream => {
+                *x = new_x;
+                *y = new_y;
+                true
+            }
+            (
+                Some(PointerAxis { dx, dy, finish }),
+                PointerAxis {
+                    dx: x,
+                    dy: y,
+                    finish: new_finish,
+                },
+            ) if !*finish => {
+                *dx += x;
+                *dy += y;
+                *finish = new_finish;
+                true
+            }
+            (
+                Some(PointerAxisDiscrete { axis, steps }),
+                PointerAxisDiscrete {
+                    axis: new_axis,
+                    steps: new_steps,
+                },
+            ) if *axis == new_axis => {
+                *steps += new_steps;
+                true
+            }
+            _ => false,
+        };
+        if !merged {
+            self.0.push(event);
+        }
+    }
+
+    /// The events of the batch, after coalescing.
+    pub fn events(&self) -> &[InputEvent] {
+        &self.0
+    }
+
+    /// Whether the batch has no events.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Remove all the events, keeping the allocated memory for the next
+    /// batch.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+impl Extend<InputEvent> for InputBatch {
+    fn extend<I: IntoIterator<Item = InputEvent>>(&mut self, iter: I) {
+        for event in iter {
+            self.push(event);
+        }
+    }
+}
+
+impl FromIterator<InputEvent> for InputBatch {
+    fn from_iter<I: IntoIterator<Item = InputEvent>>(iter: I) -> Self {
+        let mut batch = Self::new();
+        batch.extend(iter);
+        batch
+    }
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`RemoteDesktop::create_session`] request.
 #[zvariant(signature = "dict")]
@@ -164,6 +442,14 @@ struct CreateRemoteOptions {
     session_handle_token: HandleToken,
 }
 
+#[derive(SerializeDict, Type, Debug, Default)]
+/// Specified options for a [`RemoteDesktop::notify_pointer_axis`] call.
+#[zvariant(signature = "dict")]
+struct NotifyPointerAxisOptions {
+    /// Whether it is the last axis event of a scroll sequence.
+    finish: Option<bool>,
+}
+
 #[derive(SerializeDict, Type, Debug, Default)]
 /// Specified options for a [`RemoteDesktop::select_devices`] request.
 #[zvariant(signature = "dict")]
@@ -178,16 +464,19 @@ struct SelectDevicesOptions {
 
 impl SelectDevicesOptions {
     /// Sets the device types to request remote controlling of.
+    #[must_use]
     pub fn types(mut self, types: impl Into<Option<BitFlags<DeviceType>>>) -> Self {
         self.types = types.into();
         self
     }
 
+    #[must_use]
     pub fn persist_mode(mut self, persist_mode: impl Into<Option<PersistMode>>) -> Self {
         self.persist_mode = persist_mode.into();
         self
     }
 
+    #[must_use]
     pub fn restore_token<'a>(mut self, token: impl Into<Option<&'a str>>) -> Self {
         self.restore_token = token.into().map(ToOwned::to_owned);
         self
@@ -202,8 +491,8 @@ struct StartRemoteOptions {
     handle_token: HandleToken,
 }
 
-#[derive(DeserializeDict, Type, Debug, Default)]
-/// A response to a [`RemoteDesktop::select_devices`] request.
+#[derive(SerializeDict, DeserializeDict, Type, Debug, Default)]
+/// A response to a [`RemoteDesktop::start`] request.
 #[zvariant(signature = "dict")]
 pub struct SelectedDevices {
     devices: BitFlags<DeviceType>,
@@ -235,10 +524,19 @@ impl SelectedDevices {
 #[doc(alias = "org.freedesktop.portal.RemoteDesktop")]
 pub struct RemoteDesktop<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(RemoteDesktop, "org.freedesktop.portal.RemoteDesktop");
+
 impl<'a> RemoteDesktop<'a> {
     /// Create a new instance of [`RemoteDesktop`].
     pub async fn new() -> Result<RemoteDesktop<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.RemoteDesktop").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`RemoteDesktop`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<RemoteDesktop<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.RemoteDesktop").await?;
         Ok(Self(proxy))
     }
 
@@ -257,7 +555,8 @@ impl<'a> RemoteDesktop<'a> {
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future()
+            Session::from_unique_name(self.0.connection(), &options.session_handle_token)
+                .into_future()
         )?;
         assert_eq!(proxy.path(), &request.response()?.session_handle.as_ref());
         Ok(proxy)
@@ -270,6 +569,13 @@ impl<'a> RemoteDesktop<'a> {
     /// * `session` - A [`Session`], created with
     ///   [`create_session()`][`RemoteDesktop::create_session`].
     /// * `types` - The device types to request remote controlling of.
+    /// * `restore_token` - The token returned by a previous remote desktop
+    ///   session, see [`SelectedDevices::restore_token`], to resume it without
+    ///   prompting the user again.
+    /// * `persist_mode` - How long the permission of the session should be
+    ///   remembered, a new restore token is returned by
+    ///   [`start()`][`RemoteDesktop::start`] unless it is
+    ///   [`PersistMode::DoNot`].
     ///
     /// # Specifications
     ///
@@ -346,7 +652,7 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call("NotifyKeyboardKeycode", &(session, options, keycode, state))
             .await
@@ -361,7 +667,8 @@ impl<'a> RemoteDesktop<'a> {
     ///
     /// * `session` - A [`Session`], created with
     ///   [`create_session()`][`RemoteDesktop::create_session`].
-    /// * `keysym` - Keyboard symbol that was pressed or released.
+    /// * `keysym` - Keyboard symbol that was pressed or released, see
+    ///   [`keysym_from_char`] to convert a character to a keysym.
     /// * `state` - The new state of the keyboard code.
     ///
     /// # Specifications
@@ -376,12 +683,34 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call("NotifyKeyboardKeysym", &(session, options, keysym, state))
             .await
     }
 
+    /// Type a text by pressing and releasing the keysym of each of its
+    /// characters, see [`keysym_from_char`].
+    ///
+    /// **Note** only works if [`DeviceType::Keyboard`] access was provided
+    /// after starting the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - A [`Session`], created with
+    ///   [`create_session()`][`RemoteDesktop::create_session`].
+    /// * `text` - The text to type. The control characters without a keysym are
+    ///   skipped.
+    pub async fn type_text(&self, session: &Session<'_, Self>, text: &str) -> Result<(), Error> {
+        for keysym in text.chars().filter_map(keysym_from_char) {
+            self.notify_keyboard_keysym(session, keysym, KeyState::Pressed)
+                .await?;
+            self.notify_keyboard_keysym(session, keysym, KeyState::Released)
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Notify about a new touch up event.
     ///
     /// **Note** only works if [`DeviceType::Touchscreen`] access was provided
@@ -404,7 +733,7 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call("NotifyTouchUp", &(session, options, slot))
             .await
@@ -440,7 +769,7 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call("NotifyTouchDown", &(session, options, stream, slot, x, y))
             .await
@@ -476,7 +805,7 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call("NotifyTouchMotion", &(session, options, stream, slot, x, y))
             .await
@@ -507,7 +836,7 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call(
                 "NotifyPointerMotionAbsolute",
@@ -539,15 +868,13 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call("NotifyPointerMotion", &(session, options, dx, dy))
             .await
     }
 
     /// Notify pointer button.
-    /// The pointer button is encoded according to Linux Evdev button codes.
-    ///
     ///
     /// **Note** only works if [`DeviceType::Pointer`] access was provided after
     /// starting the session.
@@ -556,8 +883,9 @@ impl<'a> RemoteDesktop<'a> {
     ///
     /// * `session` - A [`Session`], created with
     ///   [`create_session()`][`RemoteDesktop::create_session`].
-    /// * `button` - The pointer button was pressed or released.
-    /// * `state` - The new state of the keyboard code.
+    /// * `button` - The pointer button that was pressed or released, either a
+    ///   [`PointerButton`] or any other Linux evdev button code.
+    /// * `state` - The new state of the button.
     ///
     /// # Specifications
     ///
@@ -566,14 +894,17 @@ impl<'a> RemoteDesktop<'a> {
     pub async fn notify_pointer_button(
         &self,
         session: &Session<'_, Self>,
-        button: i32,
+        button: impl Into<i32>,
         state: KeyState,
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
-            .call("NotifyPointerButton", &(session, options, button, state))
+            .call(
+                "NotifyPointerButton",
+                &(session, options, button.into(), state),
+            )
             .await
     }
 
@@ -587,6 +918,7 @@ impl<'a> RemoteDesktop<'a> {
     /// * `session` - A [`Session`], created with
     ///   [`create_session()`][`RemoteDesktop::create_session`].
     /// * `axis` - The axis that was scrolled.
+    /// * `steps` - The number of steps scrolled.
     ///
     /// # Specifications
     ///
@@ -600,7 +932,7 @@ impl<'a> RemoteDesktop<'a> {
     ) -> Result<(), Error> {
         // The `notify` methods don't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L723
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         self.0
             .call(
                 "NotifyPointerAxisDiscrete",
@@ -638,13 +970,87 @@ impl<'a> RemoteDesktop<'a> {
         finish: bool,
     ) -> Result<(), Error> {
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L911
-        let mut options: HashMap<&str, Value<'_>> = HashMap::new();
-        options.insert("finish", Value::Bool(finish));
+        let options = NotifyPointerAxisOptions {
+            finish: Some(finish),
+        };
         self.0
             .call("NotifyPointerAxis", &(session, options, dx, dy))
             .await
     }
 
+    /// Notify about the events of a batch, in order.
+    ///
+    /// Unlike the `notify_*` methods, the replies of the portal are not
+    /// waited for, so a batch costs a single round of writes to the bus
+    /// instead of a round-trip per event. The errors of the portal, like a
+    /// missing device access, are not reported. For higher event rates, use a
+    /// libei connection with [`RemoteDesktop::connect_to_eis`] instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - A [`Session`], created with
+    ///   [`create_session()`][`RemoteDesktop::create_session`].
+    /// * `batch` - The events to send.
+    pub async fn notify_batch(
+        &self,
+        session: &Session<'_, Self>,
+        batch: &InputBatch,
+    ) -> Result<(), Error> {
+        let options = EmptyOptions::default();
+        for event in batch.events() {
+            match *event {
+                InputEvent::PointerMotion { dx, dy } => {
+                    self.0
+                        .call_noreply("NotifyPointerMotion", &(session, &options, dx, dy))
+                        .await
+                }
+                InputEvent::PointerMotionAbsolute { stream, x, y } => {
+                    self.0
+                        .call_noreply(
+                            "NotifyPointerMotionAbsolute",
+                            &(session, &options, stream, x, y),
+                        )
+                        .await
+                }
+                InputEvent::PointerButton { button, state } => {
+                    self.0
+                        .call_noreply("NotifyPointerButton", &(session, &options, button, state))
+                        .await
+                }
+                InputEvent::PointerAxis { dx, dy, finish } => {
+                    let options = NotifyPointerAxisOptions {
+                        finish: Some(finish),
+                    };
+                    self.0
+                        .call_noreply("NotifyPointerAxis", &(session, options, dx, dy))
+                        .await
+                }
+                InputEvent::PointerAxisDiscrete { axis, steps } => {
+                    self.0
+                        .call_noreply(
+                            "NotifyPointerAxisDiscrete",
+                            &(session, &options, axis, steps),
+                        )
+                        .await
+                }
+                InputEvent::KeyboardKeycode { keycode, state } => {
+                    self.0
+                        .call_noreply(
+                            "NotifyKeyboardKeycode",
+                            &(session, &options, keycode, state),
+                        )
+                        .await
+                }
+                InputEvent::KeyboardKeysym { keysym, state } => {
+                    self.0
+                        .call_noreply("NotifyKeyboardKeysym", &(session, &options, keysym, state))
+                        .await
+                }
+            }?;
+        }
+        Ok(())
+    }
+
     /// Connect to EIS.
     ///
     /// **Note** only succeeds if called after [`RemoteDesktop::start`].
@@ -668,7 +1074,7 @@ impl<'a> RemoteDesktop<'a> {
     pub async fn connect_to_eis(&self, session: &Session<'_, Self>) -> Result<OwnedFd, Error> {
         // `ConnectToEIS` doesn't take any options for now
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/remote-desktop.c#L1464
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         let fd = self
             .0
             .call_versioned::<zvariant::OwnedFd>("ConnectToEIS", &(session, options), 2)
@@ -696,3 +1102,92 @@ impl<'a> std::ops::Deref for RemoteDesktop<'a> {
 }
 
 impl SessionPortal for RemoteDesktop<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keysym_conversion() {
+        assert_eq!(keysym_from_char('a'), Some(0x61));
+        assert_eq!(keysym_from_char('A'), Some(0x41));
+        assert_eq!(keysym_from_char(' '), Some(0x20));
+        assert_eq!(keysym_from_char('é'), Some(0xe9));
+        assert_eq!(keysym_from_char('€'), Some(0x010020ac));
+        assert_eq!(keysym_from_char('\n'), Some(0xff0d));
+        assert_eq!(keysym_from_char('\t'), Some(0xff09));
+        assert_eq!(keysym_from_char('\u{1}'), None);
+    }
+
+    #[test]
+    fn input_batch_coalescing() {
+        let mut batch = InputBatch::new();
+        batch.extend([
+            InputEvent::PointerMotion { dx: 1.0, dy: 2.0 },
+            InputEvent::PointerMotion { dx: 3.0, dy: -1.0 },
+            InputEvent::PointerButton {
+                button: PointerButton::Left.into(),
+                state: KeyState::Pressed,
+            },
+            InputEvent::PointerMotionAbsolute {
+                stream: 42,
+                x: 10.0,
+                y: 10.0,
+            },
+            InputEvent::PointerMotionAbsolute {
+                stream: 42,
+                x: 20.0,
+                y: 15.0,
+            },
+            InputEvent::PointerAxis {
+                dx: 0.0,
+                dy: 5.0,
+                finish: false,
+            },
+            InputEvent::PointerAxis {
+                dx: 0.0,
+                dy: 2.0,
+                finish: true,
+            },
+            InputEvent::PointerAxis {
+                dx: 0.0,
+                dy: 1.0,
+                finish: false,
+            },
+        ]);
+        assert_eq!(
+            batch.events(),
+            [
+                InputEvent::PointerMotion { dx: 4.0, dy: 1.0 },
+                InputEvent::PointerButton {
+                    button: PointerButton::Left.into(),
+                    state: KeyState::Pressed,
+                },
+                InputEvent::PointerMotionAbsolute {
+                    stream: 42,
+                    x: 20.0,
+                    y: 15.0,
+                },
+                InputEvent::PointerAxis {
+                    dx: 0.0,
+                    dy: 7.0,
+                    finish: true,
+                },
+                InputEvent::PointerAxis {
+                    dx: 0.0,
+                    dy: 1.0,
+                    finish: false,
+                },
+            ]
+        );
+
+        batch.clear();
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn pointer_button_serialization() {
+        assert_eq!(PointerButton::signature(), "i");
+        assert_eq!(i32::from(PointerButton::Left), 272);
+    }
+}
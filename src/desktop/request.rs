@@ -5,6 +5,58 @@ use std::{
     sync::Mutex,
 };
 
+/// Derives the deserialization of the `a{sv}` results of a portal response
+/// into a struct, along with its [`Type`].
+///
+/// Each field is decoded from the value of the key with the same name, which
+/// `#[response(rename = "key")]` changes. The unknown keys are skipped.
+///
+/// A missing key is an error, unless the field is an `Option`, left to `None`,
+/// or has a `#[response(default)]` attribute, set to its `Default` value, or
+/// `#[response(default = "path")]`, set to the value returned by the function
+/// at `path`.
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use ashpd::{
+///     desktop::{Response, ResponseResults},
+///     zvariant::{serialized::Context, to_bytes, Endian, Value},
+/// };
+///
+/// #[derive(ResponseResults, Debug)]
+/// struct PickResponse {
+///     uri: url::Url,
+///     #[response(rename = "already-open")]
+///     already_open: Option<bool>,
+///     #[response(default)]
+///     choices: Vec<(String, String)>,
+///     #[response(default = "default_scale")]
+///     scale: f64,
+/// }
+///
+/// fn default_scale() -> f64 {
+///     1.0
+/// }
+///
+/// // The body of a `org.freedesktop.portal.Request::Response` signal
+/// let mut results = HashMap::new();
+/// results.insert("uri", Value::from("file:///home/user/notes.txt"));
+/// let ctxt = Context::new_dbus(Endian::Little, 0);
+/// let body = to_bytes(ctxt, &(0u32, results)).unwrap();
+///
+/// let response: Response<PickResponse> = body.deserialize().unwrap().0;
+/// match response {
+///     Response::Ok(response) => {
+///         assert_eq!(response.uri.as_str(), "file:///home/user/notes.txt");
+///         assert_eq!(response.already_open, None);
+///         assert!(response.choices.is_empty());
+///         assert_eq!(response.scale, 1.0);
+///     }
+///     Response::Err(err) => panic!("{err}"),
+/// }
+/// ```
+pub use ashpd_macros::ResponseResults;
 use futures_util::StreamExt;
 use serde::{
     de::{self, Error as SeError, Visitor},
@@ -12,14 +64,17 @@ use serde::{
     Deserialize, Deserializer, Serialize,
 };
 use zbus::{
-    proxy::SignalStream,
     zvariant::{ObjectPath, Type, Value},
+    MatchRule, MessageStream,
 };
 
 use crate::{desktop::HandleToken, proxy::Proxy, Error};
 
 /// A typical response returned by the [`Request::response`].
 /// of a [`Request`].
+///
+/// The `a{sv}` results of the response are deserialized into `T`, usually a
+/// struct deriving [`ResponseResults`].
 #[derive(Debug, Type)]
 #[zvariant(signature = "(ua{sv})")]
 pub enum Response<T> {
@@ -163,7 +218,7 @@ impl std::fmt::Display for ResponseError {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Type)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Type)]
 /// Possible responses.
 pub enum ResponseType {
     /// Success, the request is carried out.
@@ -192,14 +247,38 @@ impl From<ResponseError> for ResponseType {
 /// The portal indicates that a portal request interaction is over by emitting
 /// the "Response" signal on the Request object.
 ///
+/// The portal methods subscribe to that signal before issuing the call and
+/// only resolve once the response was received, so there is no need to
+/// listen to it manually. The typed response is then available through
+/// [`response()`][`Request::response`], and the `send_and_wait` method of the
+/// request builders returns it right away:
+///
+/// ```rust,no_run
+/// use ashpd::desktop::Color;
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let color = Color::pick().send_and_wait().await?;
+///     println!("({}, {}, {})", color.red(), color.green(), color.blue());
+///     Ok(())
+/// }
+/// ```
+///
 /// The application can abort the interaction calling
-/// [`close()`][`Request::close`] on the Request object.
+/// [`close()`][`Request::close`] on the Request object. Dropping the future
+/// of a portal method before it resolves closes the request as well, so
+/// cancelling it, for example with `select!`, dismisses the related dialog.
+///
+/// As a drop can't wait for a D-Bus call, the `Close` call is sent from a task
+/// spawned on the executor of the connection. Nothing is sent once the
+/// response was received, nor when the future is dropped without a running
+/// Tokio runtime, like during its shutdown. [`timeout`](crate::timeout) relies
+/// on it to dismiss the dialogs nobody answered.
 ///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Request`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Request.html).
 #[doc(alias = "org.freedesktop.portal.Request")]
 pub struct Request<T>(
     Proxy<'static>,
-    SignalStream<'static>,
+    MessageStream,
     Mutex<Option<Result<T, Error>>>,
     PhantomData<T>,
 )
@@ -210,29 +289,70 @@ impl<T> Request<T>
 where
     T: for<'de> Deserialize<'de> + Type + Debug,
 {
-    pub(crate) async fn new<P>(path: P) -> Result<Request<T>, Error>
+    pub(crate) async fn new<P>(connection: zbus::Connection, path: P) -> Result<Request<T>, Error>
     where
         P: TryInto<ObjectPath<'static>>,
         P::Error: Into<zbus::Error>,
     {
-        let proxy = Proxy::new_desktop_with_path("org.freedesktop.portal.Request", path).await?;
-        // Start listening for a response signal the moment request is created
-        let stream = proxy.receive_signal("Response").await?;
+        let proxy =
+            Proxy::new_desktop_object(connection, "org.freedesktop.portal.Request", path).await?;
+        // Start listening for a response signal the moment request is created.
+        // The rule covers all the requests of the connection, as the portal
+        // might return another handle, on which the response can be emitted
+        // before the reply is received.
+        let namespace = format!(
+            "/org/freedesktop/portal/desktop/request/{}",
+            Proxy::unique_identifier(proxy.connection())?
+        );
+        let rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .sender(proxy.destination())?
+            .interface("org.freedesktop.portal.Request")?
+            .member("Response")?
+            .path_namespace(namespace)?
+            .build();
+        let stream = MessageStream::for_match_rule(rule, proxy.connection(), None).await?;
         Ok(Self(proxy, stream, Default::default(), PhantomData))
     }
 
-    pub(crate) async fn from_unique_name(handle_token: &HandleToken) -> Result<Request<T>, Error> {
-        let path =
-            Proxy::unique_name("/org/freedesktop/portal/desktop/request", handle_token).await?;
+    /// Moves the request to the handle returned by the portal, keeping the
+    /// responses already received.
+    pub(crate) async fn set_path(&mut self, path: ObjectPath<'static>) -> Result<(), Error> {
+        self.0 = Proxy::new_desktop_object(
+            self.0.connection().clone(),
+            "org.freedesktop.portal.Request",
+            path,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub(crate) async fn from_unique_name(
+        connection: &zbus::Connection,
+        handle_token: &HandleToken,
+    ) -> Result<Request<T>, Error> {
+        let path = Proxy::unique_name(
+            connection,
+            "/org/freedesktop/portal/desktop/request",
+            handle_token,
+        )
+        .await?;
         #[cfg(feature = "tracing")]
         tracing::info!("Creating a org.freedesktop.portal.Request {}", path);
-        Self::new(path).await
+        Self::new(connection.clone(), path).await
     }
 
     pub(crate) async fn prepare_response(&mut self) -> Result<(), Error> {
-        let message = self.1.next().await.ok_or(Error::NoResponse)?;
+        let message = loop {
+            let message = self.1.next().await.ok_or(Error::NoResponse)??;
+            if message.header().path() == Some(self.0.path()) {
+                break message;
+            }
+        };
         #[cfg(feature = "tracing")]
         tracing::info!("Received signal 'Response' on '{}'", self.0.interface());
+        #[cfg(feature = "debug-dump")]
+        crate::dump::message("signal", self.0.interface(), "Response", &message);
         let response = match message.body().deserialize::<Response<T>>()? {
             Response::Err(e) => Err(e.into()),
             Response::Ok(r) => Ok(r),
@@ -267,9 +387,57 @@ where
         self.0.call("Close", &()).await
     }
 
+    /// The received response if it is successful, to adjust it.
+    pub(crate) fn response_mut(&mut self) -> Option<&mut T> {
+        self.2.get_mut().unwrap().as_mut()?.as_mut().ok()
+    }
+
     pub(crate) fn path(&self) -> &ObjectPath<'_> {
         self.0.path()
     }
+
+    /// Returns a guard that closes the request when dropped, unless
+    /// [`CloseOnDrop::disarm`] is called first.
+    pub(crate) fn close_on_drop(&self) -> CloseOnDrop {
+        CloseOnDrop(Some(zbus::Proxy::clone(&self.0)))
+    }
+}
+
+/// Closes the request it was created from when dropped, used to end the user
+/// interaction when the future waiting for the response is cancelled.
+pub(crate) struct CloseOnDrop(Option<zbus::Proxy<'static>>);
+
+impl CloseOnDrop {
+    /// The request was carried out, there is nothing to close anymore.
+    pub(crate) fn disarm(mut self) {
+        self.0.take();
+    }
+}
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        let Some(proxy) = self.0.take() else {
+            return;
+        };
+        // Spawning a task requires a running runtime, which might be gone
+        // already if the future is dropped during shutdown.
+        #[cfg(feature = "tokio")]
+        if tokio::runtime::Handle::try_current().is_err() {
+            return;
+        }
+        #[cfg(feature = "tracing")]
+        tracing::info!("Closing the dropped request {}", proxy.path());
+        let connection = proxy.connection().clone();
+        connection
+            .executor()
+            .spawn(
+                async move {
+                    let _ = proxy.call_method("Close", &()).await;
+                },
+                "ashpd: close request",
+            )
+            .detach();
+    }
 }
 
 impl<T> Debug for Request<T>
@@ -302,4 +470,350 @@ mod tests {
         );
         assert_eq!(Response::<()>::signature(), "(ua{sv})");
     }
+
+    #[test]
+    fn response_codes() {
+        use zbus::zvariant::{serialized::Context, to_bytes, LE};
+
+        type Results = HashMap<String, zbus::zvariant::OwnedValue>;
+
+        let ctxt = Context::new_dbus(LE, 0);
+        let results = HashMap::<&str, Value<'_>>::new();
+        for (code, expected) in [
+            (1u32, ResponseError::Cancelled),
+            (2u32, ResponseError::Other),
+        ] {
+            let data = to_bytes(ctxt, &(code, &results)).unwrap();
+            let (response, _) = data.deserialize::<Response<Results>>().unwrap();
+            assert!(matches!(response, Response::Err(err) if err == expected));
+        }
+
+        let data = to_bytes(ctxt, &(0u32, &results)).unwrap();
+        let (response, _) = data.deserialize::<Response<Results>>().unwrap();
+        assert!(matches!(response, Response::Ok(_)));
+    }
+
+    #[test]
+    fn response_results() {
+        use zbus::zvariant::{serialized::Context, to_bytes, LE};
+
+        #[derive(ResponseResults, Debug)]
+        struct Results {
+            name: String,
+            #[response(rename = "icon-size")]
+            icon_size: Option<u32>,
+            #[response(default)]
+            tags: Vec<String>,
+        }
+
+        assert_eq!(Results::signature(), "a{sv}");
+        let ctxt = Context::new_dbus(LE, 0);
+        let decode = |results: HashMap<&str, Value<'_>>| {
+            to_bytes(ctxt, &results)
+                .unwrap()
+                .deserialize::<Results>()
+                .map(|(results, _)| results)
+        };
+
+        let results = decode(HashMap::from([
+            ("name", Value::from("notes")),
+            ("icon-size", Value::from(64u32)),
+            ("unknown", Value::from(true)),
+        ]))
+        .unwrap();
+        assert_eq!(results.name, "notes");
+        assert_eq!(results.icon_size, Some(64));
+        assert!(results.tags.is_empty());
+
+        // The required keys are missing.
+        assert!(decode(HashMap::from([("icon-size", Value::from(64u32))])).is_err());
+        // The values have to be of the type of the field.
+        assert!(decode(HashMap::from([
+            ("name", Value::from("notes")),
+            ("icon-size", Value::from("large")),
+        ]))
+        .is_err());
+    }
+
+    /// A portals frontend answering the file chooser and screenshot requests
+    /// in the reverse order they were made, answering the color requests
+    /// before replying, and leaving the account requests pending.
+    #[cfg(feature = "tokio")]
+    mod mock {
+        use std::{sync::mpsc::Sender, time::Duration};
+
+        use zbus::{
+            interface,
+            message::Header,
+            object_server::ObjectServer,
+            zvariant::{ObjectPath, OwnedObjectPath, OwnedValue},
+        };
+
+        use super::*;
+
+        fn request_path(header: &Header<'_>, token: &str) -> OwnedObjectPath {
+            let sender = header
+                .sender()
+                .unwrap()
+                .trim_start_matches(':')
+                .replace('.', "_");
+            ObjectPath::try_from(format!(
+                "/org/freedesktop/portal/desktop/request/{sender}/{token}"
+            ))
+            .unwrap()
+            .into()
+        }
+
+        fn handle_token(options: &HashMap<String, OwnedValue>) -> String {
+            String::try_from(options["handle_token"].try_clone().unwrap()).unwrap()
+        }
+
+        fn respond(
+            connection: &zbus::Connection,
+            path: OwnedObjectPath,
+            delay: u64,
+            key: &'static str,
+            value: Value<'static>,
+        ) {
+            let connection = connection.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                let results = HashMap::from([(key, value)]);
+                connection
+                    .emit_signal(
+                        None::<()>,
+                        &path,
+                        "org.freedesktop.portal.Request",
+                        "Response",
+                        &(0u32, results),
+                    )
+                    .await
+                    .unwrap();
+            });
+        }
+
+        pub struct FileChooser;
+
+        #[interface(name = "org.freedesktop.portal.FileChooser")]
+        impl FileChooser {
+            async fn open_file(
+                &self,
+                #[zbus(header)] header: Header<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                _parent_window: &str,
+                _title: &str,
+                options: HashMap<String, OwnedValue>,
+            ) -> OwnedObjectPath {
+                // A response to another request of the same sender is ignored.
+                respond(
+                    connection,
+                    request_path(&header, "ashpd_other"),
+                    0,
+                    "uris",
+                    Value::from(vec!["file:///other"]),
+                );
+                let path = request_path(&header, &handle_token(&options));
+                respond(
+                    connection,
+                    path.clone(),
+                    300,
+                    "uris",
+                    Value::from(vec!["file:///document.txt"]),
+                );
+                path
+            }
+
+            #[zbus(property, name = "version")]
+            fn version(&self) -> u32 {
+                4
+            }
+        }
+
+        pub struct Screenshot;
+
+        #[interface(name = "org.freedesktop.portal.Screenshot")]
+        impl Screenshot {
+            async fn screenshot(
+                &self,
+                #[zbus(header)] header: Header<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                _parent_window: &str,
+                _options: HashMap<String, OwnedValue>,
+            ) -> OwnedObjectPath {
+                // Like the portals ignoring the handle token.
+                let path = request_path(&header, "legacy");
+                respond(
+                    connection,
+                    path.clone(),
+                    100,
+                    "uri",
+                    Value::from("file:///screenshot.png"),
+                );
+                path
+            }
+
+            async fn pick_color(
+                &self,
+                #[zbus(header)] header: Header<'_>,
+                #[zbus(connection)] connection: &zbus::Connection,
+                _parent_window: &str,
+                _options: HashMap<String, OwnedValue>,
+            ) -> OwnedObjectPath {
+                // The response is emitted on the ignored handle before the reply.
+                let path = request_path(&header, "legacy_color");
+                let results = HashMap::from([("color", Value::from((0.5, 0.25, 1.0)))]);
+                connection
+                    .emit_signal(
+                        None::<()>,
+                        &path,
+                        "org.freedesktop.portal.Request",
+                        "Response",
+                        &(0u32, results),
+                    )
+                    .await
+                    .unwrap();
+                path
+            }
+
+            #[zbus(property, name = "version")]
+            fn version(&self) -> u32 {
+                2
+            }
+        }
+
+        /// Never answers the requests and reports those that get closed.
+        pub struct Account(pub Sender<OwnedObjectPath>);
+
+        #[interface(name = "org.freedesktop.portal.Account")]
+        impl Account {
+            async fn get_user_information(
+                &self,
+                #[zbus(header)] header: Header<'_>,
+                #[zbus(object_server)] server: &ObjectServer,
+                _window: &str,
+                options: HashMap<String, OwnedValue>,
+            ) -> OwnedObjectPath {
+                let path = request_path(&header, &handle_token(&options));
+                server
+                    .at(&path, PendingRequest(self.0.clone()))
+                    .await
+                    .unwrap();
+                path
+            }
+        }
+
+        struct PendingRequest(Sender<OwnedObjectPath>);
+
+        #[interface(name = "org.freedesktop.portal.Request")]
+        impl PendingRequest {
+            async fn close(&self, #[zbus(header)] header: Header<'_>) {
+                let path = header.path().unwrap().to_owned();
+                self.0.send(path.into()).unwrap();
+            }
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn concurrent_requests() {
+        use crate::{
+            desktop::{file_chooser::SelectedFiles, screenshot::Screenshot},
+            mock::MockBus,
+        };
+
+        let Ok(mut bus) = MockBus::new() else {
+            // No bus to run the test against.
+            return;
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            bus.serve(mock::FileChooser).await.unwrap();
+            bus.serve(mock::Screenshot).await.unwrap();
+            let connection = bus.connect().await.unwrap();
+
+            let (files, screenshot) = futures_util::try_join!(
+                SelectedFiles::open_file()
+                    .connection(connection.clone())
+                    .send(),
+                Screenshot::request().connection(connection).send(),
+            )
+            .unwrap();
+            assert_eq!(
+                files.response().unwrap().uris(),
+                [url::Url::parse("file:///document.txt").unwrap()]
+            );
+            assert_eq!(
+                screenshot.response().unwrap().uri().as_str(),
+                "file:///screenshot.png"
+            );
+        });
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn mismatched_handle() {
+        use crate::{desktop::Color, mock::MockBus};
+
+        let Ok(mut bus) = MockBus::new() else {
+            return;
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            bus.serve(mock::Screenshot).await.unwrap();
+            let connection = bus.connect().await.unwrap();
+
+            let request = Color::pick().connection(connection).send();
+            let color = tokio::time::timeout(std::time::Duration::from_secs(5), request)
+                .await
+                .expect("The response emitted before the reply was lost")
+                .unwrap()
+                .response()
+                .unwrap();
+            assert_eq!((color.red(), color.green(), color.blue()), (0.5, 0.25, 1.0));
+        });
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn dropped_request_is_closed() {
+        use std::{sync::mpsc, time::Duration};
+
+        use crate::{desktop::account::UserInformation, mock::MockBus};
+
+        let Ok(mut bus) = MockBus::new() else {
+            return;
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let (sender, closed) = mpsc::channel();
+            bus.serve(mock::Account(sender)).await.unwrap();
+            let connection = bus.connect().await.unwrap();
+
+            let request = UserInformation::request().connection(connection).send();
+            assert!(tokio::time::timeout(Duration::from_millis(100), request)
+                .await
+                .is_err());
+            // The request is closed from a spawned task.
+            for _ in 0..100 {
+                if let Ok(path) = closed.try_recv() {
+                    assert!(path.starts_with("/org/freedesktop/portal/desktop/request/"));
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            panic!("The dropped request wasn't closed");
+        });
+    }
 }
@@ -0,0 +1,335 @@
+//! Persist the restore tokens of screen cast and remote desktop sessions.
+//!
+//! The tokens are stored in the XDG state directory, in a directory named after
+//! the Flatpak or Snap id of the application, or after its executable when it
+//! isn't sandboxed. They are keyed by an application defined name, along with
+//! any other values that should be remembered between runs, like the state of
+//! a checkbox.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::{
+//!     desktop::{
+//!         restore_tokens::Tokens,
+//!         screencast::{CursorMode, Screencast, SourceType},
+//!         PersistMode,
+//!     },
+//!     WindowIdentifier,
+//! };
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let mut tokens = Tokens::load("main-capture").await?;
+//!
+//!     let proxy = Screencast::new().await?;
+//!     let session = proxy.create_session().await?;
+//!     proxy
+//!         .select_sources(
+//!             &session,
+//!             CursorMode::Metadata,
+//!             SourceType::Monitor.into(),
+//!             false,
+//!             tokens.restore_token(),
+//!             PersistMode::ExplicitlyRevoked,
+//!         )
+//!         .await?;
+//!
+//!     let response = proxy
+//!         .start(&session, &WindowIdentifier::default())
+//!         .await?
+//!         .response()?;
+//!     tokens.set_restore_token(response.restore_token());
+//!     tokens.save().await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The whole sources selection can be saved too, and the session restored from
+//! it on the next launch:
+//!
+//! ```rust,no_run
+//! use ashpd::{
+//!     desktop::{
+//!         restore_tokens::Tokens,
+//!         screencast::{CursorMode, SavedSession, Screencast, SourceType},
+//!     },
+//!     WindowIdentifier,
+//! };
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let mut tokens = Tokens::load("main-capture").await?;
+//!     let mut saved = tokens.session().unwrap_or_else(|| {
+//!         SavedSession::new(CursorMode::Metadata, SourceType::Monitor.into(), false)
+//!     });
+//!
+//!     let proxy = Screencast::new().await?;
+//!     let (_session, streams) = proxy
+//!         .restore_session(&mut saved, &WindowIdentifier::default())
+//!         .await?;
+//!     println!("{:#?}", streams.streams());
+//!
+//!     tokens.set_session(&saved);
+//!     tokens.save().await?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::{collections::BTreeMap, ffi::OsString, path::PathBuf};
+
+use enumflags2::BitFlags;
+
+use super::{
+    screencast::{CursorMode, SavedSession, SavedSource, SourceType},
+    PersistMode,
+};
+use crate::{helpers, Error};
+
+const RESTORE_TOKEN_KEY: &str = "restore_token";
+const CURSOR_MODE_KEY: &str = "cursor_mode";
+const TYPES_KEY: &str = "types";
+const MULTIPLE_KEY: &str = "multiple";
+const PERSIST_MODE_KEY: &str = "persist_mode";
+const SOURCES_KEY: &str = "sources";
+
+/// The saved restore token and values associated with a name.
+#[derive(Debug)]
+pub struct Tokens {
+    path: PathBuf,
+    entries: BTreeMap<String, String>,
+}
+
+impl Tokens {
+    /// Load the values saved under `name`.
+    ///
+    /// Nothing is loaded if no values were saved yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - An application defined name, for example `main-capture`. It
+    ///   must not be empty nor contain a `/`.
+    pub async fn load(name: &str) -> Result<Self, Error> {
+        if name.is_empty() || name.contains('/') {
+            return Err(Error::ParseError("Invalid restore tokens name"));
+        }
+        let path = state_dir()?.join(name);
+        let entries = match helpers::read_to_string(&path).await {
+            Ok(content) => parse(&content),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self { path, entries })
+    }
+
+    /// The saved restore token.
+    pub fn restore_token(&self) -> Option<&str> {
+        self.get(RESTORE_TOKEN_KEY)
+    }
+
+    /// Sets the restore token to save, or removes it when `None` is passed.
+    pub fn set_restore_token<'a>(&mut self, restore_token: impl Into<Option<&'a str>>) {
+        match restore_token.into() {
+            Some(token) => self.set(RESTORE_TOKEN_KEY, token),
+            None => {
+                self.entries.remove(RESTORE_TOKEN_KEY);
+            }
+        }
+    }
+
+    /// The saved value associated with `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Sets a value to save.
+    ///
+    /// The line breaks of the value are dropped and a `=` in the key is
+    /// replaced with a `_`.
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.entries
+            .insert(key.replace(['=', '\n'], "_"), value.replace('\n', ""));
+    }
+
+    /// The saved screen cast session, to pass to
+    /// [`Screencast::restore_session`][`super::screencast::Screencast::restore_session`].
+    pub fn session(&self) -> Option<SavedSession> {
+        let cursor_mode =
+            BitFlags::<CursorMode>::from_bits(self.get(CURSOR_MODE_KEY)?.parse().ok()?)
+                .ok()?
+                .exactly_one()?;
+        let types = BitFlags::from_bits(self.get(TYPES_KEY)?.parse().ok()?).ok()?;
+        let multiple = self.get(MULTIPLE_KEY)? == "true";
+        // Saved before the persist mode was, the sessions were always
+        // persisted until revoked.
+        let persist_mode = match self.get(PERSIST_MODE_KEY) {
+            Some("0") => PersistMode::DoNot,
+            Some("1") => PersistMode::Application,
+            _ => PersistMode::ExplicitlyRevoked,
+        };
+        let sources = self
+            .get(SOURCES_KEY)
+            .unwrap_or_default()
+            .split(';')
+            .filter(|source| !source.is_empty())
+            .map(|source| {
+                let (source_type, id) = source.split_once(':').unwrap_or((source, ""));
+                SavedSource {
+                    source_type: source_type
+                        .parse()
+                        .ok()
+                        .and_then(|bits| BitFlags::<SourceType>::from_bits(bits).ok())
+                        .and_then(BitFlags::exactly_one),
+                    id: Some(id.to_owned()).filter(|id| !id.is_empty()),
+                }
+            })
+            .collect();
+        Some(SavedSession {
+            cursor_mode,
+            types,
+            multiple,
+            persist_mode,
+            restore_token: self.restore_token().map(ToOwned::to_owned),
+            sources,
+        })
+    }
+
+    /// Sets the screen cast session to save, along with its restore token.
+    pub fn set_session(&mut self, session: &SavedSession) {
+        self.set_restore_token(session.restore_token());
+        self.set(CURSOR_MODE_KEY, &(session.cursor_mode as u32).to_string());
+        self.set(TYPES_KEY, &session.types.bits().to_string());
+        self.set(MULTIPLE_KEY, &session.multiple.to_string());
+        self.set(PERSIST_MODE_KEY, &(session.persist_mode as u32).to_string());
+        let sources = session
+            .sources
+            .iter()
+            .map(|source| {
+                format!(
+                    "{}:{}",
+                    source.source_type.map_or(0, |t| t as u32),
+                    source.id.as_deref().unwrap_or_default().replace(';', "")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        self.set(SOURCES_KEY, &sources);
+    }
+
+    /// Write the values to the disk.
+    pub async fn save(&self) -> Result<(), Error> {
+        helpers::write(&self.path, serialize(&self.entries)).await?;
+        Ok(())
+    }
+}
+
+fn state_dir() -> Result<PathBuf, Error> {
+    let state_home = match std::env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var_os("HOME").ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, "No home directory")
+            })?;
+            PathBuf::from(home).join(".local").join("state")
+        }
+    };
+    let app = app_name(
+        std::env::var_os("FLATPAK_ID").or_else(|| std::env::var_os("SNAP_INSTANCE_NAME")),
+        std::env::current_exe().ok(),
+    )
+    .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No application name"))?;
+    Ok(state_home.join("ashpd").join("restore-tokens").join(app))
+}
+
+/// The name of the directory of the application, so that the applications
+/// using the same names for their tokens don't overwrite each other's.
+fn app_name(sandbox_id: Option<OsString>, executable: Option<PathBuf>) -> Option<OsString> {
+    sandbox_id
+        .filter(|id| !id.is_empty())
+        .or_else(|| executable?.file_name().map(ToOwned::to_owned))
+}
+
+fn parse(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect()
+}
+
+fn serialize(entries: &BTreeMap<String, String>) -> String {
+    entries
+        .iter()
+        .map(|(key, value)| format!("{key}={value}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip() {
+        let mut tokens = Tokens {
+            path: PathBuf::new(),
+            entries: BTreeMap::new(),
+        };
+        tokens.set_restore_token("8d5e1cbc-3a54-4a8b-b4a8-7d4b4a0c5a6e");
+        tokens.set("show-cursor", "true");
+        tokens.set("multi\nline", "a=b\n");
+
+        let entries = parse(&serialize(&tokens.entries));
+        assert_eq!(entries, tokens.entries);
+        assert_eq!(
+            entries.get("restore_token").map(String::as_str),
+            Some("8d5e1cbc-3a54-4a8b-b4a8-7d4b4a0c5a6e")
+        );
+        assert_eq!(entries.get("multi_line").map(String::as_str), Some("a=b"));
+
+        tokens.set_restore_token(None);
+        assert_eq!(tokens.restore_token(), None);
+    }
+
+    #[test]
+    fn session_round_trip() {
+        let mut tokens = Tokens {
+            path: PathBuf::new(),
+            entries: BTreeMap::new(),
+        };
+        assert_eq!(tokens.session(), None);
+
+        let mut session = SavedSession::new(
+            CursorMode::Metadata,
+            SourceType::Monitor | SourceType::Window,
+            true,
+        );
+        session.set_persist_mode(PersistMode::Application);
+        session.restore_token = Some("8d5e1cbc".to_owned());
+        session.sources = vec![
+            SavedSource {
+                source_type: Some(SourceType::Monitor),
+                id: Some("0".to_owned()),
+            },
+            SavedSource {
+                source_type: None,
+                id: None,
+            },
+        ];
+        tokens.set_session(&session);
+
+        let entries = parse(&serialize(&tokens.entries));
+        tokens.entries = entries;
+        assert_eq!(tokens.session(), Some(session));
+        assert_eq!(tokens.restore_token(), Some("8d5e1cbc"));
+    }
+
+    #[test]
+    fn app_names() {
+        let executable = || Some(PathBuf::from("/usr/bin/obs"));
+        assert_eq!(
+            app_name(Some("com.obsproject.Studio".into()), executable()),
+            Some("com.obsproject.Studio".into())
+        );
+        assert_eq!(app_name(Some("".into()), executable()), Some("obs".into()));
+        assert_eq!(app_name(None, executable()), Some("obs".into()));
+        assert_eq!(app_name(None, None), None);
+    }
+}
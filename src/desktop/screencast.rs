@@ -41,17 +41,19 @@
 //! }
 //! ```
 
-use std::{collections::HashMap, fmt::Debug, os::fd::OwnedFd};
+use std::{fmt::Debug, os::fd::OwnedFd};
 
 use enumflags2::{bitflags, BitFlags};
 use futures_util::TryFutureExt;
-use serde::Deserialize;
+#[cfg(feature = "pipewire")]
+use pipewire::{context::Context, main_loop::MainLoop};
+use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use zbus::zvariant::{self, DeserializeDict, SerializeDict, Type, Value};
+use zbus::zvariant::{self, DeserializeDict, SerializeDict, Type};
 
 use super::{
-    remote_desktop::RemoteDesktop, session::SessionPortal, HandleToken, PersistMode, Request,
-    Session,
+    remote_desktop::RemoteDesktop, session::SessionPortal, EmptyOptions, HandleToken, PersistMode,
+    Request, Session,
 };
 use crate::{desktop::session::CreateSessionResponse, proxy::Proxy, Error, WindowIdentifier};
 
@@ -68,7 +70,11 @@ pub enum SourceType {
     /// A specific window
     Window,
     #[doc(alias = "XDP_OUTPUT_VIRTUAL")]
-    /// Virtual
+    /// A virtual monitor created by the compositor for the session, to extend
+    /// the desktop to a remote screen for example.
+    ///
+    /// Check [`Screencast::available_source_types`] for whether it is
+    /// supported, it requires version 4 of the interface.
     Virtual,
 }
 
@@ -159,8 +165,11 @@ struct StartCastOptions {
     handle_token: HandleToken,
 }
 
-#[derive(DeserializeDict, Type)]
+#[derive(SerializeDict, DeserializeDict, Type)]
 /// A response to a [`Screencast::start`] request.
+///
+/// It can be serialized with the D-Bus or GVariant encoding of [`zvariant`],
+/// for example to cache the selected sources between runs.
 #[zvariant(signature = "dict")]
 pub struct Streams {
     streams: Vec<Stream>,
@@ -177,6 +186,108 @@ impl Streams {
     pub fn streams(&self) -> &[Stream] {
         &self.streams
     }
+
+    /// The layout of the streams on the virtual desktop, to stitch the
+    /// captures of multiple monitors together.
+    pub fn layout(&self) -> DesktopLayout {
+        DesktopLayout::new(&self.streams)
+    }
+}
+
+/// The streams laid out on the virtual desktop they are part of.
+///
+/// The offsets are relative to the top left corner of the area covering all
+/// the streams, even if the compositor places some monitors at negative
+/// coordinates. Like the positions and sizes of the [`Stream`]s, they are in
+/// the compositor coordinate space and have to be scaled to the size of the
+/// stream buffers when they differ.
+///
+/// The streams without a position or a size, like the windows, are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DesktopLayout {
+    size: (i32, i32),
+    streams: Vec<StreamLayout>,
+}
+
+impl DesktopLayout {
+    /// Lay out `streams` on the virtual desktop.
+    pub fn new(streams: &[Stream]) -> Self {
+        let placed = streams
+            .iter()
+            .filter_map(|stream| {
+                Some((
+                    stream.pipe_wire_node_id(),
+                    stream.position()?,
+                    stream.size()?,
+                ))
+            })
+            .collect::<Vec<_>>();
+        let Some(left) = placed.iter().map(|(_, (x, _), _)| *x).min() else {
+            return Self::default();
+        };
+        let top = placed
+            .iter()
+            .map(|(_, (_, y), _)| *y)
+            .min()
+            .unwrap_or_default();
+        let right = placed
+            .iter()
+            .map(|(_, (x, _), (w, _))| x + w)
+            .max()
+            .unwrap_or_default();
+        let bottom = placed
+            .iter()
+            .map(|(_, (_, y), (_, h))| y + h)
+            .max()
+            .unwrap_or_default();
+        Self {
+            size: (right - left, bottom - top),
+            streams: placed
+                .into_iter()
+                .map(|(pipe_wire_node_id, (x, y), size)| StreamLayout {
+                    pipe_wire_node_id,
+                    offset: (x - left, y - top),
+                    size,
+                })
+                .collect(),
+        }
+    }
+
+    /// The (width, height) of the area covering all the streams.
+    pub fn size(&self) -> (i32, i32) {
+        self.size
+    }
+
+    /// The streams laid out, in the order of the [`Streams`].
+    pub fn streams(&self) -> &[StreamLayout] {
+        &self.streams
+    }
+}
+
+/// The place of a [`Stream`] in a [`DesktopLayout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamLayout {
+    pipe_wire_node_id: u32,
+    offset: (i32, i32),
+    size: (i32, i32),
+}
+
+impl StreamLayout {
+    /// The PipeWire stream Node ID
+    pub fn pipe_wire_node_id(&self) -> u32 {
+        self.pipe_wire_node_id
+    }
+
+    /// The (x, y) offset of the stream from the top left corner of the
+    /// layout.
+    pub fn offset(&self) -> (i32, i32) {
+        self.offset
+    }
+
+    /// The (width, height) of the stream.
+    pub fn size(&self) -> (i32, i32) {
+        self.size
+    }
 }
 
 impl Debug for Streams {
@@ -188,7 +299,7 @@ impl Debug for Streams {
     }
 }
 
-#[derive(Clone, Deserialize, Type)]
+#[derive(Clone, Serialize, Deserialize, Type)]
 /// A PipeWire stream.
 pub struct Stream(u32, StreamProperties);
 
@@ -227,8 +338,11 @@ impl Stream {
         self.1.id.as_deref()
     }
 
-    // TODO Added in version 5 of the interface.
-    /// The stream mapping id.
+    /// The identifier of the stream in the region of a remote desktop
+    /// session, to map the absolute pointer and touch events sent with
+    /// [`RemoteDesktop`] to it, a virtual monitor for example.
+    ///
+    /// Only sent by version 5 of the interface and later.
     pub fn mapping_id(&self) -> Option<&str> {
         self.1.mapping_id.as_deref()
     }
@@ -242,10 +356,121 @@ impl Debug for Stream {
             .field("size", &self.size())
             .field("source_type", &self.source_type())
             .field("id", &self.id())
+            .field("mapping_id", &self.mapping_id())
             .finish()
     }
 }
-#[derive(Clone, DeserializeDict, Type, Debug)]
+
+/// A screen cast session to re-establish on the next launch of the
+/// application with [`Screencast::restore_session`].
+///
+/// Sessions don't outlive the connection they were created over, so only what
+/// the portal can restore from is kept: the sources selection, persist mode
+/// and restore token, along with the metadata of the sources selected last
+/// time to compare against. It can be saved with
+/// [`Tokens::set_session`][`super::restore_tokens::Tokens::set_session`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedSession {
+    pub(crate) cursor_mode: CursorMode,
+    pub(crate) types: BitFlags<SourceType>,
+    pub(crate) multiple: bool,
+    pub(crate) persist_mode: PersistMode,
+    pub(crate) restore_token: Option<String>,
+    pub(crate) sources: Vec<SavedSource>,
+}
+
+impl SavedSession {
+    /// Create a new [`SavedSession`] selecting the sources as
+    /// [`Screencast::select_sources`] would, without a restore token yet.
+    ///
+    /// The session is persisted with [`PersistMode::ExplicitlyRevoked`]
+    /// unless another mode is set with [`SavedSession::set_persist_mode`].
+    pub fn new(cursor_mode: CursorMode, types: BitFlags<SourceType>, multiple: bool) -> Self {
+        Self {
+            cursor_mode,
+            types,
+            multiple,
+            persist_mode: PersistMode::ExplicitlyRevoked,
+            restore_token: None,
+            sources: Vec::new(),
+        }
+    }
+
+    /// How the cursor is drawn on the streams.
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    /// The types of content to record.
+    pub fn types(&self) -> BitFlags<SourceType> {
+        self.types
+    }
+
+    /// Whether selecting multiple sources is allowed.
+    pub fn multiple(&self) -> bool {
+        self.multiple
+    }
+
+    /// How long the portal remembers the sources selection.
+    pub fn persist_mode(&self) -> PersistMode {
+        self.persist_mode
+    }
+
+    /// Sets how long the portal remembers the sources selection.
+    ///
+    /// With [`PersistMode::DoNot`], the portal doesn't return a restore token
+    /// and the selection dialog is shown every time.
+    pub fn set_persist_mode(&mut self, persist_mode: PersistMode) {
+        self.persist_mode = persist_mode;
+    }
+
+    /// The restore token of the last session.
+    pub fn restore_token(&self) -> Option<&str> {
+        self.restore_token.as_deref()
+    }
+
+    /// The sources selected in the last session.
+    pub fn sources(&self) -> &[SavedSource] {
+        &self.sources
+    }
+
+    /// Remembers the restore token and sources of a started session.
+    ///
+    /// The restore tokens can only be used once, this has to be called after
+    /// every start of the session for the next one to be restored.
+    pub fn update(&mut self, streams: &Streams) {
+        self.restore_token = streams.restore_token.clone();
+        self.sources = streams
+            .streams()
+            .iter()
+            .map(|stream| SavedSource {
+                source_type: stream.source_type(),
+                id: stream.id().map(ToOwned::to_owned),
+            })
+            .collect();
+    }
+}
+
+/// The metadata of a source selected in a [`SavedSession`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedSource {
+    pub(crate) source_type: Option<SourceType>,
+    pub(crate) id: Option<String>,
+}
+
+impl SavedSource {
+    /// The source type of the stream.
+    pub fn source_type(&self) -> Option<SourceType> {
+        self.source_type
+    }
+
+    /// The stream identifier, which is kept across restored sessions.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+}
+
+#[derive(Clone, SerializeDict, DeserializeDict, Type, Debug)]
 /// The stream properties.
 #[zvariant(signature = "dict")]
 struct StreamProperties {
@@ -263,10 +488,19 @@ struct StreamProperties {
 #[doc(alias = "org.freedesktop.portal.ScreenCast")]
 pub struct Screencast<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(Screencast, "org.freedesktop.portal.ScreenCast");
+
 impl<'a> Screencast<'a> {
     /// Create a new instance of [`Screencast`].
     pub async fn new() -> Result<Screencast<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.ScreenCast").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Screencast`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Screencast<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.ScreenCast").await?;
         Ok(Self(proxy))
     }
 
@@ -283,7 +517,8 @@ impl<'a> Screencast<'a> {
             self.0
                 .request::<CreateSessionResponse>(&options.handle_token, "CreateSession", &options)
                 .into_future(),
-            Session::from_unique_name(&options.session_handle_token).into_future(),
+            Session::from_unique_name(self.0.connection(), &options.session_handle_token)
+                .into_future(),
         )?;
         assert_eq!(proxy.path(), &request.response()?.session_handle.as_ref());
         Ok(proxy)
@@ -311,7 +546,7 @@ impl<'a> Screencast<'a> {
     ) -> Result<OwnedFd, Error> {
         // `options` parameter doesn't seems to be used yet
         // see https://github.com/flatpak/xdg-desktop-portal/blob/master/src/screen-cast.c#L812
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         let fd = self
             .0
             .call::<zvariant::OwnedFd>("OpenPipeWireRemote", &(session, options))
@@ -395,6 +630,45 @@ impl<'a> Screencast<'a> {
             .await
     }
 
+    /// Start a screen cast session restored from a [`SavedSession`].
+    ///
+    /// The sources are selected with the restore token and persist mode of the
+    /// saved session, so the portal skips the selection dialog if it still
+    /// knows about the token. `saved` is
+    /// updated with the new restore token and sources to be saved again.
+    ///
+    /// # Arguments
+    ///
+    /// * `saved` - The session saved on the last launch, or a new one on the
+    ///   first launch.
+    /// * `identifier` - Identifier for the application window.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`CreateSession`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.ScreenCast.html#org-freedesktop-portal-screencast-createsession),
+    /// [`SelectSources`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.ScreenCast.html#org-freedesktop-portal-screencast-selectsources)
+    /// and [`Start`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.ScreenCast.html#org-freedesktop-portal-screencast-start).
+    pub async fn restore_session(
+        &self,
+        saved: &mut SavedSession,
+        identifier: &WindowIdentifier,
+    ) -> Result<(Session<'a, Self>, Streams), Error> {
+        let session = self.create_session().await?;
+        self.select_sources(
+            &session,
+            saved.cursor_mode,
+            saved.types,
+            saved.multiple,
+            saved.restore_token(),
+            saved.persist_mode,
+        )
+        .await?
+        .response()?;
+        let streams = self.start(&session, identifier).await?.response()?;
+        saved.update(&streams);
+        Ok((session, streams))
+    }
+
     /// Available cursor mode.
     ///
     /// # Specifications
@@ -414,6 +688,18 @@ impl<'a> Screencast<'a> {
     pub async fn available_source_types(&self) -> Result<BitFlags<SourceType>, Error> {
         self.0.property("AvailableSourceTypes").await
     }
+
+    /// Whether the portal supports the virtual monitors, see
+    /// [`SourceType::Virtual`].
+    pub async fn supports_virtual(&self) -> Result<bool, Error> {
+        if self.version() < 4 {
+            return Ok(false);
+        }
+        Ok(self
+            .available_source_types()
+            .await?
+            .contains(SourceType::Virtual))
+    }
 }
 
 impl<'a> std::ops::Deref for Screencast<'a> {
@@ -430,3 +716,213 @@ impl SessionPortal for Screencast<'_> {}
 pub trait HasScreencastSession: SessionPortal {}
 impl HasScreencastSession for Screencast<'_> {}
 impl HasScreencastSession for RemoteDesktop<'_> {}
+
+#[cfg(feature = "pipewire")]
+/// Connect to the PipeWire remote returned by
+/// [`Screencast::open_pipe_wire_remote`] and create a video stream for the
+/// node `node_id`, usually retrieved from
+/// [`Stream::pipe_wire_node_id`].
+///
+/// The stream is connected with the `AUTOCONNECT` and `MAP_BUFFERS` flags and
+/// advertises the common raw video formats. Callers are expected to register
+/// their listeners on the returned stream and run the returned [`MainLoop`].
+///
+/// *Note* The returned main loop and stream are not `Send`, they must be used
+/// from the thread that created them.
+#[cfg_attr(docsrs, doc(cfg(feature = "pipewire")))]
+pub fn pipewire_video_stream(
+    fd: OwnedFd,
+    node_id: u32,
+    name: &str,
+) -> Result<(MainLoop, pipewire::stream::Stream), pipewire::Error> {
+    use pipewire::{
+        spa::{
+            param::{
+                format::{FormatProperties, MediaSubtype, MediaType},
+                video::VideoFormat,
+                ParamType,
+            },
+            pod::{self, serialize::PodSerializer, Pod},
+            utils::{Direction, Fraction, Rectangle, SpaTypes},
+        },
+        stream::StreamFlags,
+    };
+
+    let mainloop = MainLoop::new(None)?;
+    let context = Context::new(&mainloop)?;
+    let core = context.connect_fd(fd, None)?;
+
+    let stream = pipewire::stream::Stream::new(
+        &core,
+        name,
+        pipewire::properties::properties! {
+            *pipewire::keys::MEDIA_TYPE => "Video",
+            *pipewire::keys::MEDIA_CATEGORY => "Capture",
+            *pipewire::keys::MEDIA_ROLE => "Screen",
+        },
+    )?;
+
+    let format = pod::object!(
+        SpaTypes::ObjectParamFormat,
+        ParamType::EnumFormat,
+        pod::property!(FormatProperties::MediaType, Id, MediaType::Video),
+        pod::property!(FormatProperties::MediaSubtype, Id, MediaSubtype::Raw),
+        pod::property!(
+            FormatProperties::VideoFormat,
+            Choice,
+            Enum,
+            Id,
+            VideoFormat::BGRx,
+            VideoFormat::BGRx,
+            VideoFormat::RGBx,
+            VideoFormat::BGRA,
+            VideoFormat::RGBA,
+            VideoFormat::RGB,
+            VideoFormat::BGR
+        ),
+        pod::property!(
+            FormatProperties::VideoSize,
+            Choice,
+            Range,
+            Rectangle,
+            Rectangle {
+                width: 1920,
+                height: 1080
+            },
+            Rectangle {
+                width: 1,
+                height: 1
+            },
+            Rectangle {
+                width: 8192,
+                height: 8192
+            }
+        ),
+        pod::property!(
+            FormatProperties::VideoFramerate,
+            Choice,
+            Range,
+            Fraction,
+            Fraction { num: 30, denom: 1 },
+            Fraction { num: 0, denom: 1 },
+            Fraction {
+                num: 1000,
+                denom: 1
+            }
+        ),
+    );
+    // Safe to unwrap, serializing a static object into a Vec can't fail
+    let values: Vec<u8> = PodSerializer::serialize(
+        std::io::Cursor::new(Vec::new()),
+        &pod::Value::Object(format),
+    )
+    .unwrap()
+    .0
+    .into_inner();
+    let mut params = [Pod::from_bytes(&values).unwrap()];
+
+    stream.connect(
+        Direction::Input,
+        Some(node_id),
+        StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+        &mut params,
+    )?;
+
+    Ok((mainloop, stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_serde() {
+        let streams = Streams {
+            streams: vec![Stream(
+                42,
+                StreamProperties {
+                    id: Some("0".to_owned()),
+                    position: Some((0, 0)),
+                    size: Some((1920, 1080)),
+                    source_type: Some(SourceType::Monitor),
+                    mapping_id: None,
+                },
+            )],
+            restore_token: Some("token".to_owned()),
+        };
+        let ctxt = zvariant::serialized::Context::new_dbus(zvariant::LE, 0);
+        let data = zvariant::to_bytes(ctxt, &streams).unwrap();
+        let (decoded, _) = data.deserialize::<Streams>().unwrap();
+        assert_eq!(decoded.restore_token(), Some("token"));
+        assert_eq!(decoded.streams()[0].pipe_wire_node_id(), 42);
+        assert_eq!(decoded.streams()[0].id(), Some("0"));
+        assert_eq!(decoded.streams()[0].size(), Some((1920, 1080)));
+        assert_eq!(
+            decoded.streams()[0].source_type(),
+            Some(SourceType::Monitor)
+        );
+    }
+
+    /// The node id, position, size and source type of a stream.
+    type StreamResponse = (u32, Option<(i32, i32)>, Option<(i32, i32)>, u32);
+
+    /// Decodes a `Start` response as sent by the backends.
+    fn response(streams: &[StreamResponse]) -> Streams {
+        let streams = streams
+            .iter()
+            .map(|&(node_id, position, size, source_type)| {
+                let mut properties = std::collections::HashMap::new();
+                if let Some(position) = position {
+                    properties.insert("position", zvariant::Value::from(position));
+                }
+                if let Some(size) = size {
+                    properties.insert("size", zvariant::Value::from(size));
+                }
+                properties.insert("source_type", zvariant::Value::from(source_type));
+                (node_id, properties)
+            })
+            .collect::<Vec<_>>();
+        let mut results = std::collections::HashMap::new();
+        results.insert("streams", zvariant::Value::from(streams));
+        let ctxt = zvariant::serialized::Context::new_dbus(zvariant::LE, 0);
+        let data = zvariant::to_bytes(ctxt, &results).unwrap();
+        data.deserialize::<Streams>().unwrap().0
+    }
+
+    #[test]
+    fn layout_side_by_side() {
+        // Two monitors of different heights, as sent by GNOME.
+        let layout = response(&[
+            (51, Some((0, 0)), Some((1920, 1080)), 1),
+            (52, Some((1920, 0)), Some((2560, 1440)), 1),
+        ])
+        .layout();
+        assert_eq!(layout.size(), (4480, 1440));
+        assert_eq!(layout.streams()[0].offset(), (0, 0));
+        assert_eq!(layout.streams()[1].pipe_wire_node_id(), 52);
+        assert_eq!(layout.streams()[1].offset(), (1920, 0));
+        assert_eq!(layout.streams()[1].size(), (2560, 1440));
+    }
+
+    #[test]
+    fn layout_negative_positions() {
+        // A monitor placed above and left of the primary one, as KDE allows,
+        // along with a window that has no position.
+        let layout = response(&[
+            (60, Some((0, 0)), Some((1920, 1080)), 1),
+            (61, Some((-1280, -1024)), Some((1280, 1024)), 1),
+            (62, None, Some((800, 600)), 2),
+        ])
+        .layout();
+        assert_eq!(layout.size(), (3200, 2104));
+        assert_eq!(layout.streams().len(), 2);
+        assert_eq!(layout.streams()[0].offset(), (1280, 1024));
+        assert_eq!(layout.streams()[1].offset(), (0, 0));
+    }
+
+    #[test]
+    fn layout_without_monitors() {
+        let layout = response(&[(70, None, Some((800, 600)), 2)]).layout();
+        assert_eq!(layout, DesktopLayout::default());
+    }
+}
@@ -27,7 +27,7 @@
 //! use ashpd::desktop::Color;
 //!
 //! async fn run() -> ashpd::Result<()> {
-//!     let color = Color::pick().send().await?.response()?;
+//!     let color = Color::pick().send_and_wait().await?;
 //!     println!("({}, {}, {})", color.red(), color.green(), color.blue());
 //!
 //!     Ok(())
@@ -75,6 +75,35 @@ impl Screenshot {
     pub fn uri(&self) -> &url::Url {
         &self.uri
     }
+
+    #[cfg(feature = "image")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    /// Reads and decodes the screenshot file.
+    pub async fn image(&self) -> Result<image::DynamicImage, Error> {
+        let bytes = crate::helpers::read(&self.path()?).await?;
+        Ok(image::load_from_memory(&bytes)?)
+    }
+
+    #[cfg(feature = "image")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    /// Reads and decodes the screenshot file, then removes it.
+    ///
+    /// This is meant for screenshots taken to be processed by the application
+    /// rather than kept by the user.
+    pub async fn take_image(self) -> Result<image::DynamicImage, Error> {
+        let path = self.path()?;
+        let bytes = crate::helpers::read(&path).await?;
+        let image = image::load_from_memory(&bytes)?;
+        crate::helpers::remove_file(&path).await?;
+        Ok(image)
+    }
+
+    #[cfg(feature = "image")]
+    fn path(&self) -> Result<std::path::PathBuf, Error> {
+        self.uri
+            .to_file_path()
+            .map_err(|_| Error::ParseError("The screenshot URI is not a local file"))
+    }
 }
 
 impl Debug for Screenshot {
@@ -93,10 +122,16 @@ struct ColorOptions {
 #[doc(alias = "org.freedesktop.portal.Screenshot")]
 struct ScreenshotProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(request ScreenshotRequest, ColorRequest => "org.freedesktop.portal.Screenshot");
+
 impl<'a> ScreenshotProxy<'a> {
-    /// Create a new instance of [`ScreenshotProxy`].
-    pub async fn new() -> Result<ScreenshotProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Screenshot").await?;
+    /// Create a new instance of [`ScreenshotProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<ScreenshotProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Screenshot").await?;
         Ok(Self(proxy))
     }
 
@@ -169,6 +204,7 @@ impl<'a> std::ops::Deref for ScreenshotProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct ColorRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     options: ColorOptions,
 }
 
@@ -180,11 +216,28 @@ impl ColorRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Build the [`Color`].
     pub async fn send(self) -> Result<Request<Color>, Error> {
-        let proxy = ScreenshotProxy::new().await?;
+        let proxy =
+            ScreenshotProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy.pick_color(&self.identifier, self.options).await
     }
+
+    /// Sends the request and waits for the [`Color`], the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<Color, Error> {
+        self.send().await?.response()
+    }
 }
 
 impl Color {
@@ -201,10 +254,28 @@ impl Color {
 #[doc(alias = "xdp_portal_take_screenshot")]
 /// A [builder-pattern] type to construct a screenshot [`Screenshot`].
 ///
+/// How the options are honored depends on the portal backend of the desktop:
+///
+/// * With recent xdg-desktop-portal versions, a non-interactive screenshot asks
+///   the user for the permission to take screenshots first, unless it was
+///   granted before and remembered in the permission store. The frontend then
+///   passes the `permission_store_checked` hint to the backend, that option is
+///   not part of the public interface and can't be set by applications.
+/// * GNOME shows its screenshot UI for an interactive screenshot and captures
+///   the whole screen otherwise.
+/// * KDE shows a dialog to pick the area to capture for an interactive
+///   screenshot and captures the whole screen otherwise.
+/// * wlroots based compositors don't support interactive screenshots and always
+///   capture the whole output.
+///
+/// The dialogs are attached to the window of the [`WindowIdentifier`], and
+/// are modal unless [`ScreenshotRequest::modal`] is set to `false`.
+///
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct ScreenshotRequest {
     options: ScreenshotOptions,
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
 }
 
 impl ScreenshotRequest {
@@ -215,7 +286,16 @@ impl ScreenshotRequest {
         self
     }
 
-    /// Sets whether the dialog should be a modal.
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
+    /// Sets whether the dialog should be a modal. Defaults to `true`.
     #[must_use]
     pub fn modal(mut self, modal: impl Into<Option<bool>>) -> Self {
         self.options.modal = modal.into();
@@ -223,7 +303,7 @@ impl ScreenshotRequest {
     }
 
     /// Sets whether the dialog should offer customization before a screenshot
-    /// or not.
+    /// or not. Defaults to `false`.
     #[must_use]
     pub fn interactive(mut self, interactive: impl Into<Option<bool>>) -> Self {
         self.options.interactive = interactive.into();
@@ -232,7 +312,28 @@ impl ScreenshotRequest {
 
     /// Build the [`Screenshot`].
     pub async fn send(self) -> Result<Request<Screenshot>, Error> {
-        let proxy = ScreenshotProxy::new().await?;
+        let proxy =
+            ScreenshotProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy.screenshot(&self.identifier, self.options).await
     }
+
+    /// Sends the request and waits for the [`Screenshot`], the same as
+    /// [`send`][`Self::send`] followed by [`Request::response`].
+    pub async fn send_and_wait(self) -> Result<Screenshot, Error> {
+        self.send().await?.response()
+    }
+}
+
+#[doc(alias = "xdp_portal_take_screenshot")]
+/// A handy wrapper around [`Screenshot::request`] that takes a
+/// non-interactive screenshot and waits for the user's response.
+pub async fn take() -> Result<Screenshot, Error> {
+    Screenshot::request().send_and_wait().await
+}
+
+#[doc(alias = "xdp_portal_pick_color")]
+/// A handy wrapper around [`Color::pick`] that waits for the user's response.
+pub async fn pick_color() -> Result<Color, Error> {
+    Color::pick().send_and_wait().await
 }
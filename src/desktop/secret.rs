@@ -51,10 +51,19 @@ struct RetrieveOptions {
 #[doc(alias = "org.freedesktop.portal.Secret")]
 pub struct Secret<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(Secret, "org.freedesktop.portal.Secret");
+
 impl<'a> Secret<'a> {
     /// Create a new instance of [`Secret`].
     pub async fn new() -> Result<Secret<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Secret").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Secret`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Secret<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Secret").await?;
         Ok(Self(proxy))
     }
 
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
 
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize, Serializer};
 use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type};
 
@@ -17,6 +17,9 @@ use crate::{desktop::HandleToken, proxy::Proxy, Error};
 /// and a signal [`Session::receive_closed`]. Whether it is allowed to
 /// directly call [`Session::close`] depends on the interface.
 ///
+/// As the compositor may end the session at any time, applications should
+/// wait on [`Session::closed`] to learn when it is no longer usable.
+///
 /// Wrapper of the DBus interface: [`org.freedesktop.portal.Session`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Session.html).
 #[derive(Type)]
 #[doc(alias = "org.freedesktop.portal.Session")]
@@ -32,23 +35,32 @@ where
     /// Create a new instance of [`Session`].
     ///
     /// **Note** A [`Session`] is not supposed to be created manually.
-    pub(crate) async fn new<P>(path: P) -> Result<Session<'a, T>, Error>
+    pub(crate) async fn new<P>(
+        connection: zbus::Connection,
+        path: P,
+    ) -> Result<Session<'a, T>, Error>
     where
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
-        let proxy = Proxy::new_desktop_with_path("org.freedesktop.portal.Session", path).await?;
+        let proxy =
+            Proxy::new_desktop_object(connection, "org.freedesktop.portal.Session", path).await?;
         Ok(Self(proxy, PhantomData))
     }
 
     pub(crate) async fn from_unique_name(
+        connection: &zbus::Connection,
         handle_token: &HandleToken,
     ) -> Result<Session<'a, T>, crate::Error> {
-        let path =
-            Proxy::unique_name("/org/freedesktop/portal/desktop/session", handle_token).await?;
+        let path = Proxy::unique_name(
+            connection,
+            "/org/freedesktop/portal/desktop/session",
+            handle_token,
+        )
+        .await?;
         #[cfg(feature = "tracing")]
         tracing::info!("Creating a org.freedesktop.portal.Session {}", path);
-        Self::new(path).await
+        Self::new(connection.clone(), path).await
     }
 
     /// Emitted when a session is closed.
@@ -57,8 +69,30 @@ where
     ///
     /// See also [`Closed`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Session.html#org-freedesktop-portal-session-closed).
     #[doc(alias = "Closed")]
-    pub async fn receive_closed(&self) -> Result<impl Stream<Item = ()>, Error> {
-        self.0.signal("Closed").await
+    pub async fn receive_closed(&self) -> Result<impl Stream<Item = ()> + Unpin, Error> {
+        // The signal comes with a details vardict whose content is defined by
+        // the interface that created the session, none of them use it yet.
+        Ok(self
+            .0
+            .signal::<HashMap<String, OwnedValue>>("Closed")
+            .await?
+            .map(|_details| ()))
+    }
+
+    /// Wait until the session is closed by the portal, for example when the
+    /// user stops sharing the screen from the compositor.
+    ///
+    /// The `Closed` signal is not emitted when the application closes the
+    /// session itself with [`Session::close`], and a session closed before
+    /// this is called is not noticed either, the future would never resolve
+    /// in both cases.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Closed`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Session.html#org-freedesktop-portal-session-closed).
+    pub async fn closed(&self) -> Result<(), Error> {
+        let mut stream = self.receive_closed().await?;
+        stream.next().await.ok_or(Error::NoResponse)
     }
 
     /// Closes the portal session to which this object refers and ends all
@@ -72,7 +106,8 @@ where
         self.0.call("Close", &()).await
     }
 
-    pub(crate) fn path(&self) -> &ObjectPath<'_> {
+    /// The session handle, the object path of the session.
+    pub fn path(&self) -> &ObjectPath<'_> {
         self.0.path()
     }
 }
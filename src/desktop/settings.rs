@@ -27,13 +27,22 @@
 //! }
 //! ```
 
-use std::{collections::HashMap, convert::TryFrom, fmt::Debug, future::ready};
-
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::Debug,
+    future::ready,
+    sync::{Arc, Mutex},
+};
+
+use futures_channel::{mpsc, oneshot};
 use futures_util::{Stream, StreamExt};
-use serde::{Deserialize, Serialize};
-use zbus::zvariant::{OwnedValue, Type, Value};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zbus::zvariant::{
+    self, serialized::Context, to_bytes, Endian, OwnedValue, Signature, Type, Value,
+};
 
-use crate::{desktop::Color, proxy::Proxy, Error};
+use crate::{desktop::Color, proxy::Proxy, Error, PortalError};
 
 /// A HashMap of the <key, value> settings found on a specific namespace.
 pub type Namespace = HashMap<String, OwnedValue>;
@@ -59,6 +68,12 @@ impl Setting {
     }
 }
 
+impl Setting {
+    fn try_clone(&self) -> Result<Self, zvariant::Error> {
+        Ok(Self(self.0.clone(), self.1.clone(), self.2.try_clone()?))
+    }
+}
+
 impl std::fmt::Debug for Setting {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Setting")
@@ -173,10 +188,19 @@ pub const CONTRAST_KEY: &str = "contrast";
 #[doc(alias = "org.freedesktop.portal.Settings")]
 pub struct Settings<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(Settings, "org.freedesktop.portal.Settings");
+
 impl<'a> Settings<'a> {
     /// Create a new instance of [`Settings`].
     pub async fn new() -> Result<Settings<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Settings").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Settings`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Settings<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Settings").await?;
         Ok(Self(proxy))
     }
 
@@ -214,7 +238,8 @@ impl<'a> Settings<'a> {
     ///
     /// # Returns
     ///
-    /// The value for `key` as a `zvariant::OwnedValue`.
+    /// The value for `key` as a `zvariant::OwnedValue`. See
+    /// [`Settings::read_as`] to deserialize it instead.
     ///
     /// # Specifications
     ///
@@ -227,11 +252,47 @@ impl<'a> Settings<'a> {
         Error: From<<T as TryFrom<OwnedValue>>::Error>,
     {
         let value = self.0.call::<OwnedValue>("Read", &(namespace, key)).await?;
-        if let Ok(v) = value.downcast_ref::<Value>() {
-            T::try_from(v.try_to_owned()?).map_err(From::from)
-        } else {
-            T::try_from(value).map_err(From::from)
-        }
+        from_value(value)
+    }
+
+    /// Reads a single value and deserializes it into `T`.
+    ///
+    /// Unlike [`Settings::read`], `T` only has to implement [`Deserialize`]
+    /// and [`Type`], like a struct deriving them.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - Namespace to look up key in.
+    /// * `key` - The key to get.
+    ///
+    /// # Returns
+    ///
+    /// The value for `key`, or a [`zvariant::Error::SignatureMismatch`] if its
+    /// signature is not the one of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use ashpd::desktop::settings::Settings;
+    ///
+    /// # async fn run() -> ashpd::Result<()> {
+    /// let settings = Settings::new().await?;
+    /// let (red, green, blue) = settings
+    ///     .read_as::<(f64, f64, f64)>("org.freedesktop.appearance", "accent-color")
+    ///     .await?;
+    /// #    Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Read`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Settings.html#org-freedesktop-portal-settings-read).
+    pub async fn read_as<T>(&self, namespace: &str, key: &str) -> Result<T, Error>
+    where
+        T: DeserializeOwned + Type,
+    {
+        let value = self.0.call::<OwnedValue>("Read", &(namespace, key)).await?;
+        deserialize_value(&value)
     }
 
     /// Retrieves the system's preferred accent color
@@ -256,7 +317,7 @@ impl<'a> Settings<'a> {
     /// Listen to changes of the system's preferred color scheme
     pub async fn receive_color_scheme_changed(
         &self,
-    ) -> Result<impl Stream<Item = ColorScheme>, Error> {
+    ) -> Result<impl Stream<Item = ColorScheme> + Unpin, Error> {
         Ok(self
             .receive_setting_changed_with_args(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY)
             .await?
@@ -264,7 +325,9 @@ impl<'a> Settings<'a> {
     }
 
     /// Listen to changes of the system's accent color
-    pub async fn receive_accent_color_changed(&self) -> Result<impl Stream<Item = Color>, Error> {
+    pub async fn receive_accent_color_changed(
+        &self,
+    ) -> Result<impl Stream<Item = Color> + Unpin, Error> {
         Ok(self
             .receive_setting_changed_with_args::<(f64, f64, f64)>(
                 APPEARANCE_NAMESPACE,
@@ -275,7 +338,9 @@ impl<'a> Settings<'a> {
     }
 
     /// Listen to changes of the system's contrast level
-    pub async fn receive_contrast_changed(&self) -> Result<impl Stream<Item = Contrast>, Error> {
+    pub async fn receive_contrast_changed(
+        &self,
+    ) -> Result<impl Stream<Item = Contrast> + Unpin, Error> {
         Ok(self
             .receive_setting_changed_with_args(APPEARANCE_NAMESPACE, CONTRAST_KEY)
             .await?
@@ -288,7 +353,9 @@ impl<'a> Settings<'a> {
     ///
     /// See also [`SettingChanged`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Settings.html#org-freedesktop-portal-settings-settingchanged).
     #[doc(alias = "SettingChanged")]
-    pub async fn receive_setting_changed(&self) -> Result<impl Stream<Item = Setting>, Error> {
+    pub async fn receive_setting_changed(
+        &self,
+    ) -> Result<impl Stream<Item = Setting> + Unpin, Error> {
         self.0.signal("SettingChanged").await
     }
 
@@ -320,7 +387,7 @@ impl<'a> Settings<'a> {
         &self,
         namespace: &str,
         key: &str,
-    ) -> Result<impl Stream<Item = Result<T, Error>>, Error>
+    ) -> Result<impl Stream<Item = Result<T, Error>> + Unpin, Error>
     where
         T: TryFrom<OwnedValue>,
         Error: From<<T as TryFrom<OwnedValue>>::Error>,
@@ -333,6 +400,45 @@ impl<'a> Settings<'a> {
     }
 }
 
+/// Converts the value, which the older versions of the portal wrap in an extra
+/// variant, into `T`.
+fn from_value<T>(value: OwnedValue) -> Result<T, Error>
+where
+    T: TryFrom<OwnedValue>,
+    Error: From<<T as TryFrom<OwnedValue>>::Error>,
+{
+    if let Ok(v) = value.downcast_ref::<Value>() {
+        T::try_from(v.try_to_owned()?).map_err(From::from)
+    } else {
+        T::try_from(value).map_err(From::from)
+    }
+}
+
+/// Deserializes the value, which the older versions of the portal wrap in an
+/// extra variant, into `T`.
+fn deserialize_value<T>(mut value: &Value<'_>) -> Result<T, Error>
+where
+    T: DeserializeOwned + Type,
+{
+    while let Value::Value(inner) = value {
+        value = inner;
+    }
+    let signature = value.value_signature();
+    if signature != T::signature() {
+        return Err(zvariant::Error::SignatureMismatch(
+            signature.to_owned(),
+            format!("`{}`", T::signature()),
+        )
+        .into());
+    }
+    // A variant is encoded as its signature followed by the value, just like a
+    // structure of a signature and the value.
+    let ctxt = Context::new_dbus(Endian::Little, 0);
+    let encoded = to_bytes(ctxt, value)?;
+    let (_, value): (Signature<'_>, T) = encoded.deserialize()?.0;
+    Ok(value)
+}
+
 impl<'a> std::ops::Deref for Settings<'a> {
     type Target = zbus::Proxy<'a>;
 
@@ -340,3 +446,294 @@ impl<'a> std::ops::Deref for Settings<'a> {
         &self.0
     }
 }
+
+/// A cache of the settings of some namespaces, kept up to date with the
+/// `SettingChanged` signal.
+///
+/// The settings are read once with `ReadAll` and then updated in a task
+/// spawned on the executor of the session connection, which is cancelled when
+/// the cache is dropped. Reading a cached value doesn't go through D-Bus.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ashpd::desktop::settings::CachedSettings;
+/// use futures_util::StreamExt;
+///
+/// # async fn run() -> ashpd::Result<()> {
+/// let settings = CachedSettings::new(&["org.freedesktop.appearance"]).await?;
+/// println!("{:#?}", settings.color_scheme()?);
+///
+/// let mut changes = settings.receive_setting_changed();
+/// while let Some(setting) = changes.next().await {
+///     println!("{} changed", setting.key());
+///     println!("{:#?}", settings.color_scheme()?);
+/// }
+/// #    Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachedSettings {
+    values: Arc<Mutex<HashMap<String, Namespace>>>,
+    senders: Arc<Mutex<Vec<mpsc::UnboundedSender<Setting>>>>,
+    _task: zbus::Task<()>,
+}
+
+impl CachedSettings {
+    /// Reads the settings of `namespaces` and starts tracking their changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespaces` - List of namespaces to cache, with the same matching
+    ///   rules as [`Settings::read_all`].
+    pub async fn new(
+        namespaces: &[impl AsRef<str> + Type + Serialize + Debug],
+    ) -> Result<Self, Error> {
+        let settings = Settings::new().await?;
+        let connection = settings.connection().clone();
+        let namespaces = namespaces
+            .iter()
+            .map(|namespace| namespace.as_ref().to_owned())
+            .collect::<Vec<_>>();
+        let values = Arc::new(Mutex::new(HashMap::new()));
+        let senders = Arc::new(Mutex::new(Vec::<mpsc::UnboundedSender<Setting>>::new()));
+        let (ready_sender, ready_receiver) = oneshot::channel();
+
+        let task = connection.executor().spawn(
+            {
+                let values = Arc::clone(&values);
+                let senders = Arc::clone(&senders);
+                async move {
+                    // Subscribe before reading the values so that no change is missed.
+                    let mut stream = match settings.receive_setting_changed().await {
+                        Ok(stream) => stream,
+                        Err(err) => {
+                            let _ = ready_sender.send(Err(err));
+                            return;
+                        }
+                    };
+                    match settings.read_all(&namespaces).await {
+                        Ok(all) => *values.lock().unwrap() = all,
+                        Err(err) => {
+                            let _ = ready_sender.send(Err(err));
+                            return;
+                        }
+                    }
+                    let _ = ready_sender.send(Ok(()));
+
+                    while let Some(setting) = stream.next().await {
+                        if !namespaces_match(&namespaces, setting.namespace()) {
+                            continue;
+                        }
+                        let Ok(value) = setting.value().try_clone() else {
+                            continue;
+                        };
+                        {
+                            // Only allocate the names of new settings.
+                            let mut values = values.lock().unwrap();
+                            if !values.contains_key(setting.namespace()) {
+                                values.insert(setting.namespace().to_owned(), Namespace::new());
+                            }
+                            let namespace = values.get_mut(setting.namespace()).unwrap();
+                            if let Some(cached) = namespace.get_mut(setting.key()) {
+                                *cached = value;
+                            } else {
+                                namespace.insert(setting.key().to_owned(), value);
+                            }
+                        }
+                        let mut senders = senders.lock().unwrap();
+                        senders.retain(|sender| !sender.is_closed());
+                        // The last listener gets the setting itself, a single
+                        // listener doesn't need a copy.
+                        if let Some((last, others)) = senders.split_last() {
+                            for sender in others {
+                                if let Ok(setting) = setting.try_clone() {
+                                    let _ = sender.unbounded_send(setting);
+                                }
+                            }
+                            let _ = last.unbounded_send(setting);
+                        }
+                    }
+                }
+            },
+            "ashpd::CachedSettings",
+        );
+
+        ready_receiver.await.map_err(|_| Error::NoResponse)??;
+        Ok(Self {
+            values,
+            senders,
+            _task: task,
+        })
+    }
+
+    /// Returns the cached value of a setting.
+    ///
+    /// Fails with [`PortalError::NotFound`] on any unknown namespace or key,
+    /// like [`Settings::read`].
+    pub fn read<T>(&self, namespace: &str, key: &str) -> Result<T, Error>
+    where
+        T: TryFrom<OwnedValue>,
+        Error: From<<T as TryFrom<OwnedValue>>::Error>,
+    {
+        from_value(self.value(namespace, key)?)
+    }
+
+    /// Returns the cached value of a setting, deserialized like
+    /// [`Settings::read_as`].
+    pub fn read_as<T>(&self, namespace: &str, key: &str) -> Result<T, Error>
+    where
+        T: DeserializeOwned + Type,
+    {
+        let value = self.value(namespace, key)?;
+        deserialize_value(&value)
+    }
+
+    /// Returns the cached values of all the settings.
+    pub fn read_all(&self) -> Result<HashMap<String, Namespace>, Error> {
+        self.values
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(namespace, values)| {
+                let values = values
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), value.try_clone()?)))
+                    .collect::<Result<Namespace, Error>>()?;
+                Ok((namespace.clone(), values))
+            })
+            .collect()
+    }
+
+    /// The system's preferred accent color.
+    pub fn accent_color(&self) -> Result<Color, Error> {
+        self.read::<(f64, f64, f64)>(APPEARANCE_NAMESPACE, ACCENT_COLOR_SCHEME_KEY)
+            .map(Color::from)
+    }
+
+    /// The system's preferred color scheme.
+    pub fn color_scheme(&self) -> Result<ColorScheme, Error> {
+        self.read::<ColorScheme>(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY)
+    }
+
+    /// The system's preferred contrast level.
+    pub fn contrast(&self) -> Result<Contrast, Error> {
+        self.read::<Contrast>(APPEARANCE_NAMESPACE, CONTRAST_KEY)
+    }
+
+    /// Listen to the changes of the cached settings.
+    ///
+    /// The cache is already updated when a change is received from the
+    /// stream.
+    pub fn receive_setting_changed(&self) -> impl Stream<Item = Setting> + Unpin {
+        let (sender, receiver) = mpsc::unbounded();
+        self.senders.lock().unwrap().push(sender);
+        receiver
+    }
+
+    fn value(&self, namespace: &str, key: &str) -> Result<OwnedValue, Error> {
+        let values = self.values.lock().unwrap();
+        let value = values
+            .get(namespace)
+            .and_then(|values| values.get(key))
+            .ok_or_else(|| {
+                PortalError::NotFound(format!("Requested setting {namespace}.{key} not found"))
+            })?;
+        Ok(value.try_clone()?)
+    }
+}
+
+/// Whether `namespace` is matched by one of `patterns`, an empty list matching
+/// all the namespaces like with `ReadAll`.
+fn namespaces_match(patterns: &[String], namespace: &str) -> bool {
+    patterns.is_empty()
+        || patterns
+            .iter()
+            .any(|pattern| namespace_matches(pattern, namespace))
+}
+
+/// Whether `namespace` is matched by `pattern`, globing only the trailing
+/// sections like `org.example.*`.
+fn namespace_matches(pattern: &str, namespace: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        _ if pattern.is_empty() => true,
+        Some(prefix) => namespace.starts_with(prefix),
+        None => pattern == namespace,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::DeserializeDict;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_value_types() {
+        let value = Value::Value(Box::new(Value::from("12h")));
+        assert_eq!(deserialize_value::<String>(&value).unwrap(), "12h");
+
+        let value = Value::from((0.2, 0.4, 0.6));
+        assert_eq!(
+            deserialize_value::<(f64, f64, f64)>(&value).unwrap(),
+            (0.2, 0.4, 0.6)
+        );
+
+        #[derive(DeserializeDict, Type, Debug, PartialEq)]
+        #[zvariant(signature = "dict")]
+        struct Font {
+            name: String,
+            size: Option<u32>,
+        }
+        let mut dict = HashMap::new();
+        dict.insert("name", Value::from("Cantarell"));
+        dict.insert("size", Value::from(11u32));
+        let font = deserialize_value::<Font>(&Value::from(dict)).unwrap();
+        assert_eq!(
+            font,
+            Font {
+                name: "Cantarell".to_owned(),
+                size: Some(11)
+            }
+        );
+
+        assert!(matches!(
+            deserialize_value::<u32>(&Value::from("12h")),
+            Err(Error::Zbus(zbus::Error::Variant(
+                zvariant::Error::SignatureMismatch(..)
+            )))
+        ));
+    }
+
+    #[test]
+    fn namespace_patterns() {
+        assert!(namespace_matches("", "org.freedesktop.appearance"));
+        assert!(namespace_matches(
+            "org.freedesktop.appearance",
+            "org.freedesktop.appearance"
+        ));
+        assert!(namespace_matches(
+            "org.freedesktop.*",
+            "org.freedesktop.appearance"
+        ));
+        assert!(!namespace_matches(
+            "org.gnome.desktop.*",
+            "org.freedesktop.appearance"
+        ));
+        assert!(!namespace_matches(
+            "org.freedesktop",
+            "org.freedesktop.appearance"
+        ));
+    }
+
+    #[test]
+    fn namespace_lists() {
+        assert!(namespaces_match(&[], "org.freedesktop.appearance"));
+        let patterns = ["org.gnome.*".to_owned(), "org.kde.kdeglobals".to_owned()];
+        assert!(namespaces_match(&patterns, "org.gnome.desktop.interface"));
+        assert!(namespaces_match(&patterns, "org.kde.kdeglobals"));
+        assert!(!namespaces_match(&patterns, "org.freedesktop.appearance"));
+    }
+}
@@ -15,6 +15,17 @@
 //! }
 //! ```
 //!
+//! Or by passing the path of the file
+//!
+//! ```rust,no_run
+//! use ashpd::desktop::trash;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     trash::trash_path("/home/bilelmoussaoui/adwaita-night.jpg").await?;
+//!     Ok(())
+//! }
+//! ```
+//!
 //! Or by using the Proxy directly
 //!
 //! ```rust,no_run
@@ -30,7 +41,10 @@
 //! }
 //! ```
 
-use std::os::fd::BorrowedFd;
+use std::{
+    os::fd::{AsFd, BorrowedFd},
+    path::Path,
+};
 
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{Fd, Type};
@@ -51,16 +65,26 @@ enum TrashStatus {
 #[doc(alias = "org.freedesktop.portal.Trash")]
 pub struct TrashProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(TrashProxy, "org.freedesktop.portal.Trash");
+
 impl<'a> TrashProxy<'a> {
     /// Create a new instance of [`TrashProxy`].
     pub async fn new() -> Result<TrashProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Trash").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`TrashProxy`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<TrashProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Trash").await?;
         Ok(Self(proxy))
     }
 
     /// Sends a file to the trashcan.
     /// Applications are allowed to trash a file if they can open it in
-    /// read/write mode.
+    /// read/write mode, or if it can be written to when the descriptor is
+    /// opened with `O_PATH`.
     ///
     /// # Arguments
     ///
@@ -97,6 +121,39 @@ pub async fn trash_file(fd: &BorrowedFd<'_>) -> Result<(), Error> {
     proxy.trash_file(fd).await
 }
 
+#[doc(alias = "xdp_portal_trash_file")]
+/// A handy wrapper around [`TrashProxy::trash_file`] taking the path of the
+/// file instead.
+///
+/// The file is only opened with `O_PATH`, so directories and the files the
+/// application can't open for writing can be trashed as well, as long as the
+/// portal allows it.
+pub async fn trash_path(path: impl AsRef<Path>) -> Result<(), Error> {
+    let fd = crate::helpers::open_path(path.as_ref())?;
+    trash_file(&fd.as_fd()).await
+}
+
+#[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))))]
+#[doc(alias = "xdp_portal_trash_file")]
+/// A handy wrapper around [`trash_path`] taking a
+/// [`gio::File`](gtk4::gio::File) instead.
+///
+/// Only local files can be trashed. Like with [`trash_path`], the file is
+/// opened with `O_PATH`, which allows to trash directories and read-only
+/// files.
+pub async fn trash_gio_file(
+    file: &impl gtk4::glib::prelude::IsA<gtk4::gio::File>,
+) -> Result<(), Error> {
+    use gtk4::gio::prelude::FileExt;
+
+    let path = file
+        .as_ref()
+        .path()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Not a local file"))?;
+    trash_path(path).await
+}
+
 #[cfg(test)]
 mod test {
     use super::TrashStatus;
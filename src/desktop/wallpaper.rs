@@ -95,11 +95,13 @@ impl From<SetOn> for &'static str {
 impl FromStr for SetOn {
     type Err = Error;
 
+    /// Parses both the values of the `set-on` option, like `lockscreen`, and
+    /// the [`Display`](fmt::Display) form, like `Lockscreen`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Lockscreen" => Ok(SetOn::Lockscreen),
-            "Background" => Ok(SetOn::Background),
-            "Both" => Ok(SetOn::Both),
+        match s.to_ascii_lowercase().as_str() {
+            "lockscreen" => Ok(SetOn::Lockscreen),
+            "background" => Ok(SetOn::Background),
+            "both" => Ok(SetOn::Both),
             _ => Err(Error::ParseError("Failed to parse SetOn, invalid value")),
         }
     }
@@ -117,9 +119,13 @@ struct WallpaperOptions {
 
 struct WallpaperProxy<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(request WallpaperRequest => "org.freedesktop.portal.Wallpaper");
+
 impl<'a> WallpaperProxy<'a> {
-    pub async fn new() -> Result<WallpaperProxy<'a>, Error> {
-        let proxy = Proxy::new_desktop("org.freedesktop.portal.Wallpaper").await?;
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<WallpaperProxy<'a>, Error> {
+        let proxy = Proxy::new_desktop(connection, "org.freedesktop.portal.Wallpaper").await?;
         Ok(Self(proxy))
     }
 
@@ -170,6 +176,7 @@ impl<'a> std::ops::Deref for WallpaperProxy<'a> {
 /// [builder-pattern]: https://doc.rust-lang.org/1.0.0/style/ownership/builders.html
 pub struct WallpaperRequest {
     identifier: WindowIdentifier,
+    connection: Option<zbus::Connection>,
     options: WallpaperOptions,
 }
 
@@ -181,6 +188,15 @@ impl WallpaperRequest {
         self
     }
 
+    #[must_use]
+    /// Sets the connection to send the request over.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub fn connection(mut self, connection: impl Into<Option<zbus::Connection>>) -> Self {
+        self.connection = connection.into();
+        self
+    }
+
     /// Whether to show a preview of the picture.
     /// **Note** the portal may decide to show a preview even if this option is
     /// not set.
@@ -199,7 +215,9 @@ impl WallpaperRequest {
 
     /// Build using a URI.
     pub async fn build_uri(self, uri: &url::Url) -> Result<Request<()>, Error> {
-        let proxy = WallpaperProxy::new().await?;
+        let proxy =
+            WallpaperProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy
             .set_wallpaper_uri(&self.identifier, uri, self.options)
             .await
@@ -207,7 +225,9 @@ impl WallpaperRequest {
 
     /// Build using a file.
     pub async fn build_file(self, file: &BorrowedFd<'_>) -> Result<Request<()>, Error> {
-        let proxy = WallpaperProxy::new().await?;
+        let proxy =
+            WallpaperProxy::with_connection(Proxy::connection_or_shared(self.connection).await?)
+                .await?;
         proxy
             .set_wallpaper_file(&self.identifier, file, self.options)
             .await
@@ -215,7 +235,11 @@ impl WallpaperRequest {
 }
 #[cfg(test)]
 mod tests {
-    use super::SetOn;
+    use std::collections::HashMap;
+
+    use zbus::zvariant::{serialized::Context, to_bytes, Endian, OwnedValue};
+
+    use super::*;
 
     #[test]
     fn serialize_deserialize() {
@@ -226,4 +250,26 @@ mod tests {
         let decoded = serde_json::from_str(&string).unwrap();
         assert_eq!(set_on, decoded);
     }
+
+    #[test]
+    fn set_on_option() {
+        let options = WallpaperOptions {
+            set_on: Some(SetOn::Lockscreen),
+            ..Default::default()
+        };
+        let ctxt = Context::new_dbus(Endian::Little, 0);
+        let encoded = to_bytes(ctxt, &options).unwrap();
+        let decoded: HashMap<String, OwnedValue> = encoded.deserialize().unwrap().0;
+        assert_eq!(
+            String::try_from(decoded["set-on"].try_clone().unwrap()).unwrap(),
+            "lockscreen"
+        );
+
+        assert_eq!("lockscreen".parse::<SetOn>().unwrap(), SetOn::Lockscreen);
+        assert_eq!(
+            SetOn::Both.to_string().parse::<SetOn>().unwrap(),
+            SetOn::Both
+        );
+        assert!("everywhere".parse::<SetOn>().is_err());
+    }
 }
@@ -22,12 +22,12 @@
 //! }
 //! ```
 
-use std::{collections::HashMap, os::fd::BorrowedFd};
+use std::os::fd::BorrowedFd;
 
 use futures_util::Stream;
-use zbus::zvariant::{Fd, SerializeDict, Type, Value};
+use zbus::zvariant::{Fd, SerializeDict, Type};
 
-use crate::{proxy::Proxy, Error};
+use crate::{desktop::EmptyOptions, proxy::Proxy, Error};
 
 #[derive(SerializeDict, Debug, Type, Default)]
 /// Specified options for a [`FileTransfer::start_transfer`] request.
@@ -77,10 +77,24 @@ impl TransferOptions {
 #[doc(alias = "org.freedesktop.portal.FileTransfer")]
 pub struct FileTransfer<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(
+    FileTransfer,
+    "org.freedesktop.portal.FileTransfer",
+    DOCUMENTS_DESTINATION,
+    DOCUMENTS_PATH
+);
+
 impl<'a> FileTransfer<'a> {
     /// Create a new instance of [`FileTransfer`].
     pub async fn new() -> Result<FileTransfer<'a>, Error> {
-        let proxy = Proxy::new_documents("org.freedesktop.portal.FileTransfer").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`FileTransfer`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<FileTransfer<'a>, Error> {
+        let proxy = Proxy::new_documents(connection, "org.freedesktop.portal.FileTransfer").await?;
         Ok(Self(proxy))
     }
 
@@ -100,7 +114,7 @@ impl<'a> FileTransfer<'a> {
     #[doc(alias = "AddFiles")]
     pub async fn add_files(&self, key: &str, fds: &[&BorrowedFd<'_>]) -> Result<(), Error> {
         // `options` parameter doesn't seems to be used yet
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
         let files: Vec<Fd> = fds.iter().map(Fd::from).collect();
 
         self.0.call("AddFiles", &(key, files, options)).await
@@ -127,7 +141,7 @@ impl<'a> FileTransfer<'a> {
     pub async fn retrieve_files(&self, key: &str) -> Result<Vec<String>, Error> {
         // `options` parameter doesn't seems to be used yet
         // see https://github.com/GNOME/gtk/blob/master/gdk/filetransferportal.c#L284
-        let options: HashMap<&str, Value<'_>> = HashMap::new();
+        let options = EmptyOptions::default();
 
         self.0.call("RetrieveFiles", &(key, options)).await
     }
@@ -188,7 +202,7 @@ impl<'a> FileTransfer<'a> {
     ///
     /// See also [`TransferClosed`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.FileTransfer.html#org-freedesktop-portal-filetransfer-transferclosed).
     #[doc(alias = "TransferClosed")]
-    pub async fn transfer_closed(&self) -> Result<impl Stream<Item = String>, Error> {
+    pub async fn transfer_closed(&self) -> Result<impl Stream<Item = String> + Unpin, Error> {
         self.0.signal("TransferClosed").await
     }
 }
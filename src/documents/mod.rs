@@ -23,6 +23,10 @@
 //!     proxy
 //!         .grant_permissions("f2ee988d", &app_id, &[Permission::GrantPermissions])
 //!         .await?;
+//!     proxy.grant_read("f2ee988d", &app_id).await?;
+//!     for (app_id, permissions) in proxy.permissions("f2ee988d").await? {
+//!         println!("{app_id}: {permissions:?}");
+//!     }
 //!     proxy
 //!         .revoke_permissions("f2ee988d", &app_id, &[Permission::Write])
 //!         .await?;
@@ -149,10 +153,24 @@ impl FromStr for Permission {
 #[doc(alias = "org.freedesktop.portal.Documents")]
 pub struct Documents<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(
+    Documents,
+    "org.freedesktop.portal.Documents",
+    DOCUMENTS_DESTINATION,
+    DOCUMENTS_PATH
+);
+
 impl<'a> Documents<'a> {
     /// Create a new instance of [`Documents`].
     pub async fn new() -> Result<Documents<'a>, Error> {
-        let proxy = Proxy::new_documents("org.freedesktop.portal.Documents").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Documents`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Documents<'a>, Error> {
+        let proxy = Proxy::new_documents(connection, "org.freedesktop.portal.Documents").await?;
         Ok(Self(proxy))
     }
 
@@ -479,6 +497,65 @@ impl<'a> Documents<'a> {
             .await
     }
 
+    /// Grants an application the permission to read a file in the document
+    /// store.
+    ///
+    /// See also [`grant_permissions()`][`Documents::grant_permissions`].
+    pub async fn grant_read(
+        &self,
+        doc_id: impl Into<DocumentID>,
+        app_id: &AppID,
+    ) -> Result<(), Error> {
+        self.grant_permissions(doc_id, app_id, &[Permission::Read])
+            .await
+    }
+
+    /// Grants an application the permissions to read and write a file in the
+    /// document store.
+    ///
+    /// See also [`grant_permissions()`][`Documents::grant_permissions`].
+    pub async fn grant_write(
+        &self,
+        doc_id: impl Into<DocumentID>,
+        app_id: &AppID,
+    ) -> Result<(), Error> {
+        self.grant_permissions(doc_id, app_id, &[Permission::Read, Permission::Write])
+            .await
+    }
+
+    /// Revokes all the permissions of an application for a file in the
+    /// document store.
+    ///
+    /// See also [`revoke_permissions()`][`Documents::revoke_permissions`].
+    pub async fn revoke_all(
+        &self,
+        doc_id: impl Into<DocumentID>,
+        app_id: &AppID,
+    ) -> Result<(), Error> {
+        self.revoke_permissions(
+            doc_id,
+            app_id,
+            &[
+                Permission::Read,
+                Permission::Write,
+                Permission::GrantPermissions,
+                Permission::Delete,
+            ],
+        )
+        .await
+    }
+
+    /// Gets the permissions of every application for a file in the document
+    /// store.
+    ///
+    /// **Note** This call is not available inside the sandbox.
+    ///
+    /// See also [`info()`][`Documents::info`].
+    pub async fn permissions(&self, doc_id: impl Into<DocumentID>) -> Result<Permissions, Error> {
+        let (_, permissions) = self.info(doc_id).await?;
+        Ok(permissions)
+    }
+
     /// Retrieves the host filesystem paths from their document IDs.
     ///
     /// # Arguments
@@ -514,6 +591,11 @@ mod file_transfer;
 
 pub use file_transfer::FileTransfer;
 
+/// Interact with `org.freedesktop.impl.portal.PermissionStore` interface.
+mod permission_store;
+
+pub use permission_store::{AppPermissions, PermissionStore, PermissionStoreChanged};
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
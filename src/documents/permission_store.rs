@@ -0,0 +1,318 @@
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ashpd::documents::PermissionStore;
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let proxy = PermissionStore::new().await?;
+//!
+//!     for id in proxy.list("notifications").await? {
+//!         let (permissions, _data) = proxy.lookup("notifications", &id).await?;
+//!         println!("{id}: {permissions:#?}");
+//!     }
+//!
+//!     proxy
+//!         .set_permission(
+//!             "notifications",
+//!             true,
+//!             "notification",
+//!             "org.gnome.Maps",
+//!             &["no"],
+//!         )
+//!         .await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use futures_util::Stream;
+use serde::Deserialize;
+use zbus::zvariant::{OwnedValue, Type, Value};
+
+use crate::{proxy::Proxy, Error};
+
+/// A [`HashMap`] mapping application IDs to the permissions stored for them in
+/// a permission store entry.
+///
+/// The meaning of the permissions depends on the table the entry belongs to,
+/// for example `yes` or `no` for the `notifications` table.
+pub type AppPermissions = HashMap<String, Vec<String>>;
+
+/// Notifies about a change of an entry in the permission store.
+#[derive(Debug, Deserialize, Type)]
+pub struct PermissionStoreChanged(String, String, bool, OwnedValue, AppPermissions);
+
+impl PermissionStoreChanged {
+    /// The name of the table.
+    pub fn table(&self) -> &str {
+        &self.0
+    }
+
+    /// The ID of the entry.
+    pub fn id(&self) -> &str {
+        &self.1
+    }
+
+    /// Whether the entry was deleted.
+    pub fn deleted(&self) -> bool {
+        self.2
+    }
+
+    /// The data associated with the entry.
+    pub fn data(&self) -> &OwnedValue {
+        &self.3
+    }
+
+    /// The new permissions of the entry.
+    pub fn permissions(&self) -> &AppPermissions {
+        &self.4
+    }
+}
+
+/// The permission store can be used by portals to store permissions that
+/// sandboxed applications have to various resources, such as files outside the
+/// sandbox. It is mostly useful for settings panels and other privileged tools
+/// that let the user inspect and edit those permissions.
+///
+/// Entries are organized in tables, each entry having an ID, a map of
+/// application IDs to permissions and optional data.
+///
+/// **Note** This interface is not available inside the sandbox.
+///
+/// Wrapper of the DBus interface: [`org.freedesktop.impl.portal.PermissionStore`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html).
+#[derive(Debug)]
+#[doc(alias = "org.freedesktop.impl.portal.PermissionStore")]
+pub struct PermissionStore<'a>(Proxy<'a>);
+
+crate::proxy::impl_portal!(
+    PermissionStore,
+    "org.freedesktop.impl.portal.PermissionStore",
+    PERMISSION_STORE_DESTINATION,
+    PERMISSION_STORE_PATH
+);
+
+impl<'a> PermissionStore<'a> {
+    /// Create a new instance of [`PermissionStore`].
+    pub async fn new() -> Result<PermissionStore<'a>, Error> {
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`PermissionStore`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(
+        connection: zbus::Connection,
+    ) -> Result<PermissionStore<'a>, Error> {
+        let proxy =
+            Proxy::new_permission_store(connection, "org.freedesktop.impl.portal.PermissionStore")
+                .await?;
+        Ok(Self(proxy))
+    }
+
+    /// Looks up the permissions and data of an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    /// * `id` - The resource ID to look up.
+    ///
+    /// # Returns
+    ///
+    /// The [`AppPermissions`] of the entry along with its data.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Lookup`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-lookup).
+    #[doc(alias = "Lookup")]
+    pub async fn lookup(
+        &self,
+        table: &str,
+        id: &str,
+    ) -> Result<(AppPermissions, OwnedValue), Error> {
+        self.0.call("Lookup", &(table, id)).await
+    }
+
+    /// Writes the permissions and data of an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    /// * `create` - Whether to create the table if it does not exist.
+    /// * `id` - The resource ID to modify.
+    /// * `app_permissions` - The permissions to store.
+    /// * `data` - The data to store.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Set`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-set).
+    #[doc(alias = "Set")]
+    pub async fn set(
+        &self,
+        table: &str,
+        create: bool,
+        id: &str,
+        app_permissions: &AppPermissions,
+        data: &Value<'_>,
+    ) -> Result<(), Error> {
+        self.0
+            .call("Set", &(table, create, id, app_permissions, data))
+            .await
+    }
+
+    /// Writes the data of an entry, keeping its permissions.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    /// * `create` - Whether to create the table if it does not exist.
+    /// * `id` - The resource ID to modify.
+    /// * `data` - The data to store.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`SetValue`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-setvalue).
+    #[doc(alias = "SetValue")]
+    pub async fn set_value(
+        &self,
+        table: &str,
+        create: bool,
+        id: &str,
+        data: &Value<'_>,
+    ) -> Result<(), Error> {
+        self.0.call("SetValue", &(table, create, id, data)).await
+    }
+
+    /// Sets the permissions of an application for an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    /// * `create` - Whether to create the table if it does not exist.
+    /// * `id` - The resource ID to modify.
+    /// * `app` - The application ID to modify.
+    /// * `permissions` - The permissions to set.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`SetPermission`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-setpermission).
+    #[doc(alias = "SetPermission")]
+    pub async fn set_permission(
+        &self,
+        table: &str,
+        create: bool,
+        id: &str,
+        app: &str,
+        permissions: &[&str],
+    ) -> Result<(), Error> {
+        self.0
+            .call("SetPermission", &(table, create, id, app, permissions))
+            .await
+    }
+
+    /// Removes the permissions of an application for an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    /// * `id` - The resource ID to modify.
+    /// * `app` - The application ID to modify.
+    ///
+    /// # Required version
+    ///
+    /// The method requires the 2nd version implementation of the interface
+    /// and would fail with [`Error::RequiresVersion`] otherwise.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`DeletePermission`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-deletepermission).
+    #[doc(alias = "DeletePermission")]
+    pub async fn delete_permission(&self, table: &str, id: &str, app: &str) -> Result<(), Error> {
+        self.0
+            .call_versioned("DeletePermission", &(table, id, app), 2)
+            .await
+    }
+
+    /// Returns the permissions of an application for an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    /// * `id` - The resource ID to look up.
+    /// * `app` - The application ID to look up.
+    ///
+    /// # Required version
+    ///
+    /// The method requires the 2nd version implementation of the interface
+    /// and would fail with [`Error::RequiresVersion`] otherwise.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`GetPermission`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-getpermission).
+    #[doc(alias = "GetPermission")]
+    pub async fn permission(&self, table: &str, id: &str, app: &str) -> Result<Vec<String>, Error> {
+        self.0
+            .call_versioned("GetPermission", &(table, id, app), 2)
+            .await
+    }
+
+    /// Removes an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    /// * `id` - The resource ID to delete.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Delete`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-delete).
+    #[doc(alias = "Delete")]
+    pub async fn delete(&self, table: &str, id: &str) -> Result<(), Error> {
+        self.0.call("Delete", &(table, id)).await
+    }
+
+    /// Returns the IDs of all the entries of a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The name of the table to use.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`List`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-list).
+    #[doc(alias = "List")]
+    pub async fn list(&self, table: &str) -> Result<Vec<String>, Error> {
+        self.0.call("List", &(table)).await
+    }
+
+    /// Emitted when the permissions or the data of an entry change.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`Changed`](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.impl.portal.PermissionStore.html#org-freedesktop-impl-portal-permissionstore-changed).
+    #[doc(alias = "Changed")]
+    pub async fn receive_changed(
+        &self,
+    ) -> Result<impl Stream<Item = PermissionStoreChanged> + Unpin, Error> {
+        self.0.signal("Changed").await
+    }
+}
+
+impl<'a> std::ops::Deref for PermissionStore<'a> {
+    type Target = zbus::Proxy<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_signature() {
+        assert_eq!(PermissionStoreChanged::signature(), "(ssbva{sas})");
+    }
+}
@@ -0,0 +1,128 @@
+//! Dumps of the portal calls, responses and signals, recorded with the
+//! `ashpd::dump` tracing target when the `debug-dump` feature is enabled.
+
+use serde::Serialize;
+use zbus::{
+    zvariant::{serialized::Context, to_bytes, Endian, Structure, StructureBuilder, Type, Value},
+    Message,
+};
+
+/// The keys of the options and results whose value is never dumped.
+const SECRET_KEYS: &[&str] = &["restore_token", "token"];
+
+const REDACTED: &str = "<redacted>";
+
+/// Dumps an outgoing method call along with its arguments.
+pub(crate) fn call<B: Serialize + Type>(interface: &str, method: &str, body: &B) {
+    // The arguments of the call are the fields of `body` if it is a structure.
+    let args = if B::signature().starts_with('(') {
+        encode_args(body)
+    } else {
+        encode_args(&(body,))
+    };
+    match args {
+        Ok(args) => tracing::debug!(target: "ashpd::dump", "Calling {interface}.{method}{args}"),
+        Err(err) => {
+            tracing::debug!(target: "ashpd::dump", "Calling {interface}.{method}(<{err}>)")
+        }
+    }
+}
+
+/// Dumps an incoming method reply or signal.
+pub(crate) fn message(kind: &str, interface: &str, name: &str, msg: &Message) {
+    let body = msg.body();
+    let args = if body
+        .signature()
+        .map_or(true, |signature| signature.is_empty())
+    {
+        Ok("()".to_owned())
+    } else {
+        body.deserialize::<Structure<'_>>().map(format_args)
+    };
+    match args {
+        Ok(args) => {
+            tracing::debug!(target: "ashpd::dump", "Received {kind} {interface}.{name}{args}")
+        }
+        Err(err) => {
+            tracing::debug!(target: "ashpd::dump", "Received {kind} {interface}.{name}(<{err}>)")
+        }
+    }
+}
+
+fn encode_args<T: Serialize + Type>(args: &T) -> zbus::zvariant::Result<String> {
+    let ctxt = Context::new_dbus(Endian::Little, 0);
+    let encoded = to_bytes(ctxt, args)?;
+    let (args, _) = encoded.deserialize_for_dynamic_signature(T::signature())?;
+    Ok(format_args(args))
+}
+
+fn format_args(args: Structure<'_>) -> String {
+    let args = args
+        .into_fields()
+        .into_iter()
+        .map(|arg| redact(arg).to_string())
+        .collect::<Vec<_>>();
+    format!("({})", args.join(", "))
+}
+
+/// Replaces the values of the [`SECRET_KEYS`] of the dictionaries.
+fn redact(value: Value<'_>) -> Value<'_> {
+    match value {
+        Value::Value(inner) => Value::Value(Box::new(redact(*inner))),
+        Value::Structure(structure) => Value::Structure(
+            structure
+                .into_fields()
+                .into_iter()
+                .fold(StructureBuilder::new(), |builder, field| {
+                    builder.append_field(redact(field))
+                })
+                .build(),
+        ),
+        Value::Dict(mut dict) => {
+            for (key, value) in dict.iter_mut() {
+                let is_secret =
+                    matches!(key, Value::Str(key) if SECRET_KEYS.contains(&key.as_str()));
+                let inner = std::mem::replace(value, Value::Bool(false));
+                *value = match inner {
+                    Value::Value(_) if is_secret => Value::Value(Box::new(Value::from(REDACTED))),
+                    _ if is_secret => Value::from(REDACTED),
+                    inner => redact(inner),
+                };
+            }
+            Value::Dict(dict)
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn redact_secrets() {
+        let mut options = HashMap::new();
+        options.insert("restore_token", Value::from("8d5e1cbc"));
+        options.insert("persist_mode", Value::from(2u32));
+        let args = encode_args(&("/org/freedesktop/portal/desktop", options)).unwrap();
+
+        assert!(!args.contains("8d5e1cbc"), "{args}");
+        assert!(args.contains(REDACTED), "{args}");
+        assert!(args.contains("persist_mode"), "{args}");
+
+        let mut results = HashMap::new();
+        results.insert("restore_token", Value::from("8d5e1cbc"));
+        let msg = Message::signal(
+            "/org/freedesktop/portal/desktop/request/1_42/t",
+            "org.freedesktop.portal.Request",
+            "Response",
+        )
+        .unwrap()
+        .build(&(0u32, results))
+        .unwrap();
+        let args = format_args(msg.body().deserialize::<Structure<'_>>().unwrap());
+        assert_eq!(args, r#"(uint32 0, {"restore_token": <"<redacted>">})"#);
+    }
+}
@@ -1,6 +1,9 @@
 use zbus::DBusError;
 
-use crate::desktop::{dynamic_launcher::UnexpectedIconError, request::ResponseError};
+use crate::desktop::{
+    dynamic_launcher::{InvalidIconError, UnexpectedIconError},
+    request::ResponseError,
+};
 
 /// An error type that describes the various DBus errors.
 ///
@@ -19,6 +22,7 @@ pub enum PortalError {
     /// Not found.
     NotFound(String),
     /// Exists already.
+    #[zbus(name = "Exists")]
     Exist(String),
     /// Method not allowed to be called.
     NotAllowed(String),
@@ -40,6 +44,8 @@ pub enum Error {
     Zbus(zbus::Error),
     /// A signal returned no response.
     NoResponse,
+    /// The portal request didn't complete in time.
+    TimedOut,
     /// Failed to parse a string into an enum variant
     ParseError(&'static str),
     /// Input/Output
@@ -47,6 +53,9 @@ pub enum Error {
     /// A pipewire error
     #[cfg(feature = "pipewire")]
     Pipewire(pipewire::Error),
+    /// Failed to decode an image
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
     /// Invalid AppId
     ///
     /// See <https://developer.gnome.org/documentation/tutorials/application-id.html#rules-for-application-ids>
@@ -60,10 +69,14 @@ pub enum Error {
     RequiresVersion(u32, u32),
     /// Returned when the portal wasn't found. Either the user has no portals
     /// frontend installed or the frontend doesn't support the used portal.
+    ///
+    /// The inner field is the name of the missing interface.
     PortalNotFound(zbus::names::OwnedInterfaceName),
     /// An error indicating that a Icon::Bytes was expected but wrong type was
     /// passed
     UnexpectedIcon,
+    /// The icon doesn't satisfy the constraints enforced by the portal.
+    InvalidIcon(InvalidIconError),
     #[cfg(feature = "backend")]
     /// Failed to parse a URL.
     Url(url::ParseError),
@@ -78,9 +91,12 @@ impl std::fmt::Display for Error {
             Self::Zbus(e) => f.write_str(&format!("ZBus Error: {e}")),
             Self::Portal(e) => f.write_str(&format!("Portal request failed: {e}")),
             Self::NoResponse => f.write_str("Portal error: no response"),
+            Self::TimedOut => f.write_str("Portal request timed out"),
             Self::IO(e) => f.write_str(&format!("IO: {e}")),
             #[cfg(feature = "pipewire")]
             Self::Pipewire(e) => f.write_str(&format!("Pipewire: {e}")),
+            #[cfg(feature = "image")]
+            Self::Image(e) => f.write_str(&format!("Image: {e}")),
             Self::ParseError(e) => f.write_str(e),
             Self::InvalidAppID => f.write_str("Invalid app id"),
             Self::NulTerminated(u) => write!(f, "Nul byte found in provided data at position {u}"),
@@ -95,6 +111,7 @@ impl std::fmt::Display for Error {
                 f,
                 "Expected icon of type Icon::Bytes but a different type was used."
             ),
+            Self::InvalidIcon(e) => f.write_str(&format!("Invalid icon: {e}")),
             #[cfg(feature = "backend")]
             Self::Url(e) => f.write_str(&format!("Parse error: {e}")),
         }
@@ -120,6 +137,13 @@ impl From<pipewire::Error> for Error {
     }
 }
 
+#[cfg(feature = "image")]
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
 impl From<zbus::fdo::Error> for Error {
     fn from(e: zbus::fdo::Error) -> Self {
         Self::Zbus(zbus::Error::FDO(Box::new(e)))
@@ -127,8 +151,13 @@ impl From<zbus::fdo::Error> for Error {
 }
 
 impl From<zbus::Error> for Error {
+    /// The errors replied by a portal are mapped to [`Error::Portal`], the
+    /// others to [`Error::Zbus`].
     fn from(e: zbus::Error) -> Self {
-        Self::Zbus(e)
+        match PortalError::from(e) {
+            PortalError::ZBus(e) => Self::Zbus(e),
+            e => Self::Portal(e),
+        }
     }
 }
 
@@ -149,9 +178,61 @@ impl From<UnexpectedIconError> for Error {
         Self::UnexpectedIcon
     }
 }
+
+impl From<InvalidIconError> for Error {
+    fn from(e: InvalidIconError) -> Self {
+        Self::InvalidIcon(e)
+    }
+}
+
 #[cfg(feature = "backend")]
 impl From<url::ParseError> for Error {
     fn from(e: url::ParseError) -> Self {
         Self::Url(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zbus::{message::Message, DBusError};
+
+    use super::{Error, PortalError};
+
+    #[test]
+    fn portal_error_names() {
+        assert_eq!(
+            PortalError::NotAllowed(String::new()).name(),
+            "org.freedesktop.portal.Error.NotAllowed"
+        );
+        assert_eq!(
+            PortalError::Exist(String::new()).name(),
+            "org.freedesktop.portal.Error.Exists"
+        );
+    }
+
+    #[test]
+    fn portal_error_replies() {
+        let call = Message::method("/org/freedesktop/portal/desktop", "Pick")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let reply = |name| {
+            Message::method_error(&call, name)
+                .unwrap()
+                .build(&("Denied",))
+                .unwrap()
+        };
+
+        let err = Error::from(zbus::Error::from(reply(
+            "org.freedesktop.portal.Error.NotAllowed",
+        )));
+        assert!(
+            matches!(err, Error::Portal(PortalError::NotAllowed(ref detail)) if detail == "Denied")
+        );
+
+        let err = Error::from(zbus::Error::from(reply(
+            "org.freedesktop.DBus.Error.UnknownMethod",
+        )));
+        assert!(matches!(err, Error::Zbus(zbus::Error::MethodError(..))));
+    }
+}
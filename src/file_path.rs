@@ -8,6 +8,20 @@ use serde::{Deserialize, Serialize};
 use zbus::zvariant::Type;
 
 /// A file name represented as a nul-terminated byte array.
+///
+/// The requests taking a path also accept a `file` URI converted with
+/// [`FilePath::try_from`], which decodes its percent-encoding:
+///
+/// ```rust
+/// use ashpd::{url::Url, FilePath};
+///
+/// let uri = Url::parse("file:///home/user/My%20Pictures").unwrap();
+/// let path = FilePath::try_from(&uri).unwrap();
+/// assert_eq!(
+///     path.as_ref(),
+///     std::path::Path::new("/home/user/My Pictures")
+/// );
+/// ```
 #[derive(Type, Debug, Default, PartialEq)]
 #[zvariant(signature = "ay")]
 pub struct FilePath(CString);
@@ -27,6 +41,17 @@ impl FilePath {
     }
 }
 
+impl TryFrom<&url::Url> for FilePath {
+    type Error = crate::Error;
+
+    fn try_from(uri: &url::Url) -> Result<Self, Self::Error> {
+        let path = uri
+            .to_file_path()
+            .map_err(|()| crate::Error::ParseError("The URI is not a local file"))?;
+        Self::new(path)
+    }
+}
+
 impl Serialize for FilePath {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -85,4 +110,16 @@ mod tests {
         assert_eq!(decoded, file_path);
         assert_eq!(decoded, file_path_2);
     }
+
+    #[test]
+    fn from_uri() {
+        let uri = url::Url::parse("file:///tmp/a%20b").unwrap();
+        assert_eq!(
+            FilePath::try_from(&uri).unwrap(),
+            FilePath::new("/tmp/a b").unwrap()
+        );
+
+        let uri = url::Url::parse("https://example.org/a").unwrap();
+        assert!(FilePath::try_from(&uri).is_err());
+    }
 }
@@ -6,6 +6,7 @@ use std::{collections::HashMap, os::fd::BorrowedFd, path::Path};
 
 use enumflags2::{bitflags, BitFlags};
 use futures_util::Stream;
+use serde::Deserialize;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use zbus::zvariant::{Fd, Type};
 
@@ -24,6 +25,42 @@ pub enum HostCommandFlags {
     WatchBus,
 }
 
+/// Notifies about a process started by
+/// [`host_command()`][`Development::host_command`] exiting.
+#[derive(Debug, Deserialize, Type, Clone, Copy, PartialEq, Eq)]
+pub struct HostCommandExited(u32, u32);
+
+impl HostCommandExited {
+    /// The PID of the process.
+    pub fn pid(&self) -> u32 {
+        self.0
+    }
+
+    /// The raw wait status of the process, as returned by `waitpid()`.
+    pub fn wait_status(&self) -> u32 {
+        self.1
+    }
+
+    /// The exit code of the process, if it exited normally.
+    pub fn exit_code(&self) -> Option<i32> {
+        if self.1 & 0x7f == 0 {
+            Some(((self.1 >> 8) & 0xff) as i32)
+        } else {
+            None
+        }
+    }
+
+    /// The signal that terminated the process, if it was killed by one.
+    pub fn signal(&self) -> Option<i32> {
+        let signal = self.1 & 0x7f;
+        if signal != 0 && signal != 0x7f {
+            Some(signal as i32)
+        } else {
+            None
+        }
+    }
+}
+
 /// The Development interface lets any client, possibly in a sandbox if it has
 /// access to the session helper, spawn a process on the host, outside any
 /// sandbox.
@@ -33,10 +70,26 @@ pub enum HostCommandFlags {
 #[doc(alias = "org.freedesktop.Flatpak.Development")]
 pub struct Development<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(
+    Development,
+    "org.freedesktop.Flatpak.Development",
+    FLATPAK_DEVELOPMENT_DESTINATION,
+    FLATPAK_DEVELOPMENT_PATH
+);
+
 impl<'a> Development<'a> {
     /// Create a new instance of [`Development`]
     pub async fn new() -> Result<Development<'a>, Error> {
-        let proxy = Proxy::new_flatpak_development("org.freedesktop.Flatpak.Development").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Development`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Development<'a>, Error> {
+        let proxy =
+            Proxy::new_flatpak_development(connection, "org.freedesktop.Flatpak.Development")
+                .await?;
         Ok(Self(proxy))
     }
 
@@ -47,7 +100,27 @@ impl<'a> Development<'a> {
     ///
     /// See also [`HostCommandExited`](https://docs.flatpak.org/en/latest/libflatpak-api-reference.html#gdbus-signal-org-freedesktop-Flatpak-Development.HostCommandExited).
     #[doc(alias = "HostCommandExited")]
-    pub async fn receive_spawn_exited(&self) -> Result<impl Stream<Item = (u32, u32)>, Error> {
+    #[deprecated(note = "Use `receive_host_command_exited` instead")]
+    pub async fn receive_spawn_exited(
+        &self,
+    ) -> Result<impl Stream<Item = (u32, u32)> + Unpin, Error> {
+        self.0.signal("HostCommandExited").await
+    }
+
+    /// Emitted when a process started by
+    /// [`host_command()`][`Development::host_command`] exits.
+    ///
+    /// **Note** Start listening before calling
+    /// [`host_command()`][`Development::host_command`] so that the signal of a
+    /// short lived process is not missed.
+    ///
+    /// # Specifications
+    ///
+    /// See also [`HostCommandExited`](https://docs.flatpak.org/en/latest/libflatpak-api-reference.html#gdbus-signal-org-freedesktop-Flatpak-Development.HostCommandExited).
+    #[doc(alias = "HostCommandExited")]
+    pub async fn receive_host_command_exited(
+        &self,
+    ) -> Result<impl Stream<Item = HostCommandExited> + Unpin, Error> {
         self.0.signal("HostCommandExited").await
     }
 
@@ -59,10 +132,11 @@ impl<'a> Development<'a> {
     /// * `cwd_path` - The working directory for the new process.
     /// * `argv` - The argv for the new process, starting with the executable to
     ///   launch.
-    /// * `fds` - Array of file descriptors to pass to the new process.
+    /// * `fds` - The file descriptors to pass to the new process, keyed by the
+    ///   file descriptor number they get in the new process.
     /// * `envs` - Array of variable/value pairs for the environment of the new
     ///   process.
-    /// * `flags`
+    /// * `flags` - The [`HostCommandFlags`] of the new process.
     ///
     /// # Returns
     ///
@@ -71,6 +145,7 @@ impl<'a> Development<'a> {
     /// # Specifications
     ///
     /// See also [`HostCommand`](https://docs.flatpak.org/en/latest/libflatpak-api-reference.html#gdbus-method-org-freedesktop-Flatpak-Development.HostCommand).
+    #[doc(alias = "HostCommand")]
     pub async fn host_command(
         &self,
         cwd_path: impl AsRef<Path>,
@@ -102,8 +177,7 @@ impl<'a> Development<'a> {
     /// # Specifications
     ///
     /// See also [`HostCommandSignal`](https://docs.flatpak.org/en/latest/libflatpak-api-reference.html#gdbus-method-org-freedesktop-Flatpak-Development.HostCommandSignal).
-    #[doc(alias = "SpawnSignal")]
-    #[doc(alias = "xdp_portal_spawn_signal")]
+    #[doc(alias = "HostCommandSignal")]
     pub async fn host_command_signal(
         &self,
         pid: u32,
@@ -123,3 +197,23 @@ impl<'a> std::ops::Deref for Development<'a> {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_command_exited_status() {
+        let exited = HostCommandExited(42, 3 << 8);
+        assert_eq!(exited.pid(), 42);
+        assert_eq!(exited.exit_code(), Some(3));
+        assert_eq!(exited.signal(), None);
+
+        // Killed by SIGKILL
+        let killed = HostCommandExited(42, 9);
+        assert_eq!(killed.exit_code(), None);
+        assert_eq!(killed.signal(), Some(9));
+
+        assert_eq!(HostCommandExited::signature(), "(uu)");
+    }
+}
@@ -227,6 +227,27 @@ impl SpawnOptions {
         self.app_fd = fd.into().map(|f| f.into());
         self
     }
+
+    /// Checks the options for values the portal would reject.
+    fn validate(&self) -> Result<(), Error> {
+        let exposed = self.sandbox_expose.iter().chain(&self.sandbox_expose_ro);
+        if exposed
+            .flatten()
+            .any(|name| name.is_empty() || name == "." || name == ".." || name.contains('/'))
+        {
+            return Err(Error::ParseError(
+                "Exposed sandbox files must be plain file names",
+            ));
+        }
+        if self.unset_env.iter().flatten().any(|var| !is_env_var(var)) {
+            return Err(Error::ParseError("Invalid environment variable name"));
+        }
+        Ok(())
+    }
+}
+
+fn is_env_var(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['=', '\0'])
 }
 
 #[derive(SerializeDict, Type, Debug, Default)]
@@ -245,10 +266,24 @@ struct CreateMonitorOptions {}
 #[doc(alias = "org.freedesktop.portal.Flatpak")]
 pub struct Flatpak<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(
+    Flatpak,
+    "org.freedesktop.portal.Flatpak",
+    FLATPAK_DESTINATION,
+    FLATPAK_PATH
+);
+
 impl<'a> Flatpak<'a> {
     /// Create a new instance of [`Flatpak`].
     pub async fn new() -> Result<Flatpak<'a>, Error> {
-        let proxy = Proxy::new_flatpak("org.freedesktop.portal.Flatpak").await?;
+        Self::with_connection(Proxy::connection().await?).await
+    }
+
+    /// Create a new instance of [`Flatpak`] that uses `connection`.
+    ///
+    /// See [`crate::set_session_connection`].
+    pub async fn with_connection(connection: zbus::Connection) -> Result<Flatpak<'a>, Error> {
+        let proxy = Proxy::new_flatpak(connection, "org.freedesktop.portal.Flatpak").await?;
         Ok(Self(proxy))
     }
 
@@ -273,7 +308,7 @@ impl<'a> Flatpak<'a> {
             .call_versioned::<OwnedObjectPath>("CreateUpdateMonitor", &(options), 2)
             .await?;
 
-        UpdateMonitor::new(path.into_inner()).await
+        UpdateMonitor::new(self.0.connection().clone(), path.into_inner()).await
     }
 
     /// Emitted when a process starts by [`spawn()`][`Flatpak::spawn`].
@@ -282,7 +317,9 @@ impl<'a> Flatpak<'a> {
     ///
     /// See also [`SpawnStarted`](https://docs.flatpak.org/en/latest/portal-api-reference.html#gdbus-signal-org-freedesktop-portal-Flatpak.SpawnStarted).
     #[doc(alias = "SpawnStarted")]
-    pub async fn receive_spawn_started(&self) -> Result<impl Stream<Item = (u32, u32)>, Error> {
+    pub async fn receive_spawn_started(
+        &self,
+    ) -> Result<impl Stream<Item = (u32, u32)> + Unpin, Error> {
         self.0.signal("SpawnStarted").await
     }
 
@@ -294,7 +331,9 @@ impl<'a> Flatpak<'a> {
     /// See also [`SpawnExited`](https://docs.flatpak.org/en/latest/portal-api-reference.html#gdbus-signal-org-freedesktop-portal-Flatpak.SpawnExited).
     #[doc(alias = "SpawnExited")]
     #[doc(alias = "XdpPortal::spawn-exited")]
-    pub async fn receive_spawn_exited(&self) -> Result<impl Stream<Item = (u32, u32)>, Error> {
+    pub async fn receive_spawn_exited(
+        &self,
+    ) -> Result<impl Stream<Item = (u32, u32)> + Unpin, Error> {
         self.0.signal("SpawnExited").await
     }
 
@@ -309,9 +348,12 @@ impl<'a> Flatpak<'a> {
     /// * `fds` - Array of file descriptors to pass to the new process.
     /// * `envs` - Array of variable/value pairs for the environment of the new
     ///   process.
-    /// * `flags`
+    /// * `flags` - The [`SpawnFlags`] of the new process.
     /// * `options` - A [`SpawnOptions`].
     ///
+    /// Fails with [`Error::ParseError`] without calling the portal if one of
+    /// the environment variables or of the exposed files names is invalid.
+    ///
     /// # Returns
     ///
     /// The PID of the new process.
@@ -330,6 +372,10 @@ impl<'a> Flatpak<'a> {
         flags: BitFlags<SpawnFlags>,
         options: SpawnOptions,
     ) -> Result<u32, Error> {
+        if !envs.keys().all(|var| is_env_var(var)) {
+            return Err(Error::ParseError("Invalid environment variable name"));
+        }
+        options.validate()?;
         let cwd_path = FilePath::new(cwd_path)?;
         let argv = argv
             .iter()
@@ -392,4 +438,35 @@ pub use update_monitor::{UpdateInfo, UpdateMonitor, UpdateProgress, UpdateStatus
 
 /// Provide for a way to execute processes outside of the sandbox
 mod development;
-pub use development::{Development, HostCommandFlags};
+pub use development::{Development, HostCommandExited, HostCommandFlags};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawn_options_validation() {
+        let options = SpawnOptions::default()
+            .sandbox_expose(["data.db"])
+            .sandbox_expose_ro(["config.ini"])
+            .unset_env(["LANG"]);
+        assert!(options.validate().is_ok());
+
+        for name in ["", ".", "..", "/etc/passwd", "sub/dir"] {
+            assert!(SpawnOptions::default()
+                .sandbox_expose([name])
+                .validate()
+                .is_err());
+            assert!(SpawnOptions::default()
+                .sandbox_expose_ro([name])
+                .validate()
+                .is_err());
+        }
+
+        assert!(SpawnOptions::default()
+            .unset_env(["LANG=C"])
+            .validate()
+            .is_err());
+        assert!(SpawnOptions::default().unset_env([""]).validate().is_err());
+    }
+}
@@ -116,15 +116,23 @@ pub struct UpdateProgress {
 #[doc(alias = "org.freedesktop.portal.Flatpak.UpdateMonitor")]
 pub struct UpdateMonitor<'a>(Proxy<'a>);
 
+crate::proxy::impl_portal!(UpdateMonitor);
+
 impl<'a> UpdateMonitor<'a> {
     /// Create a new instance of [`UpdateMonitor`].
     ///
     /// **Note** A [`UpdateMonitor`] is not supposed to be created
     /// manually.
-    pub(crate) async fn new(path: ObjectPath<'a>) -> Result<UpdateMonitor<'a>, Error> {
-        let proxy =
-            Proxy::new_flatpak_with_path("org.freedesktop.portal.Flatpak.UpdateMonitor", path)
-                .await?;
+    pub(crate) async fn new(
+        connection: zbus::Connection,
+        path: ObjectPath<'a>,
+    ) -> Result<UpdateMonitor<'a>, Error> {
+        let proxy = Proxy::new_flatpak_with_path(
+            connection,
+            "org.freedesktop.portal.Flatpak.UpdateMonitor",
+            path,
+        )
+        .await?;
         Ok(Self(proxy))
     }
 
@@ -135,7 +143,9 @@ impl<'a> UpdateMonitor<'a> {
     /// See also [`Progress`](https://docs.flatpak.org/en/latest/portal-api-reference.html#gdbus-signal-org-freedesktop-portal-Flatpak-UpdateMonitor.Progress).
     #[doc(alias = "Progress")]
     #[doc(alias = "XdpPortal::update-progress")]
-    pub async fn receive_progress(&self) -> Result<impl Stream<Item = UpdateProgress>, Error> {
+    pub async fn receive_progress(
+        &self,
+    ) -> Result<impl Stream<Item = UpdateProgress> + Unpin, Error> {
         self.0.signal("Progress").await
     }
 
@@ -146,7 +156,9 @@ impl<'a> UpdateMonitor<'a> {
     /// See also [`UpdateAvailable`](https://docs.flatpak.org/en/latest/portal-api-reference.html#gdbus-signal-org-freedesktop-portal-Flatpak-UpdateMonitor.UpdateAvailable).
     #[doc(alias = "UpdateAvailable")]
     #[doc(alias = "XdpPortal::update-available")]
-    pub async fn receive_update_available(&self) -> Result<impl Stream<Item = UpdateInfo>, Error> {
+    pub async fn receive_update_available(
+        &self,
+    ) -> Result<impl Stream<Item = UpdateInfo> + Unpin, Error> {
         self.0.signal("UpdateAvailable").await
     }
 
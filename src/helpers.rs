@@ -1,3 +1,11 @@
+use std::{
+    future::Future,
+    io,
+    os::fd::{FromRawFd, OwnedFd},
+    path::Path,
+    time::Duration,
+};
+
 #[cfg(feature = "async-std")]
 use async_fs::File;
 #[cfg(feature = "async-std")]
@@ -10,12 +18,97 @@ pub(crate) async fn is_flatpak() -> bool {
     {
         async_fs::metadata("/.flatpak-info").await.is_ok()
     }
-    #[cfg(not(feature = "async-std"))]
+    #[cfg(feature = "tokio")]
+    {
+        tokio::fs::try_exists("/.flatpak-info")
+            .await
+            .unwrap_or(false)
+    }
+}
+
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    #[cfg(feature = "async-std")]
+    {
+        use futures_util::future::{select, Either};
+
+        let timer = async_io::Timer::after(duration);
+        match select(std::pin::pin!(future), timer).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(_) => None,
+        }
+    }
+    #[cfg(feature = "tokio")]
+    {
+        tokio::time::timeout(duration, future).await.ok()
+    }
+}
+
+#[cfg(feature = "image")]
+pub(crate) async fn read(path: &Path) -> io::Result<Vec<u8>> {
+    #[cfg(feature = "async-std")]
+    {
+        async_fs::read(path).await
+    }
+    #[cfg(feature = "tokio")]
+    {
+        tokio::fs::read(path).await
+    }
+}
+
+#[cfg(feature = "image")]
+pub(crate) async fn remove_file(path: &Path) -> io::Result<()> {
+    #[cfg(feature = "async-std")]
+    {
+        async_fs::remove_file(path).await
+    }
+    #[cfg(feature = "tokio")]
     {
-        std::path::PathBuf::from("/.flatpak-info").exists()
+        tokio::fs::remove_file(path).await
     }
 }
 
+pub(crate) async fn read_to_string(path: &Path) -> io::Result<String> {
+    #[cfg(feature = "async-std")]
+    {
+        async_fs::read_to_string(path).await
+    }
+    #[cfg(feature = "tokio")]
+    {
+        tokio::fs::read_to_string(path).await
+    }
+}
+
+/// Write `content` to `path`, creating its parent directories if needed.
+pub(crate) async fn write(path: &Path, content: String) -> io::Result<()> {
+    #[cfg(feature = "async-std")]
+    {
+        if let Some(parent) = path.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+        async_fs::write(path, content).await
+    }
+    #[cfg(feature = "tokio")]
+    {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, content).await
+    }
+}
+
+/// Opens `path` with `O_PATH`, the portals only need the file descriptor to
+/// identify the file, which allows to pass directories and read-only files.
+pub(crate) fn open_path(path: &Path) -> io::Result<OwnedFd> {
+    use nix::{
+        fcntl::{open, OFlag},
+        sys::stat::Mode,
+    };
+
+    let fd = open(path, OFlag::O_PATH | OFlag::O_CLOEXEC, Mode::empty())?;
+    // SAFETY: the file descriptor was just opened and isn't owned elsewhere.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
 pub(crate) async fn is_snap() -> bool {
     let pid = std::process::id();
     let path = format!("/proc/{pid}/cgroup");
@@ -79,4 +172,18 @@ mod tests {
 0::/user.slice/user-1000.slice/user@1000.service/apps.slice/apps-org.gnome.Terminal.slice/vte-spawn-228ae109-a869-4533-8988-65ea4c10b492.scope\n";
         assert!(cgroup_v2_is_snap(data));
     }
+
+    #[test]
+    fn open_path_directory() {
+        use std::os::fd::AsRawFd;
+
+        use nix::fcntl::{fcntl, FcntlArg, FdFlag, OFlag};
+
+        let fd = open_path(&std::env::temp_dir()).unwrap();
+        let flags = OFlag::from_bits_truncate(fcntl(fd.as_raw_fd(), FcntlArg::F_GETFL).unwrap());
+        assert!(flags.contains(OFlag::O_PATH));
+        let fd_flags =
+            FdFlag::from_bits_truncate(fcntl(fd.as_raw_fd(), FcntlArg::F_GETFD).unwrap());
+        assert!(fd_flags.contains(FdFlag::FD_CLOEXEC));
+    }
 }
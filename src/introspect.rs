@@ -0,0 +1,138 @@
+use zbus::{
+    fdo::{IntrospectableProxy, PropertiesProxy},
+    names::InterfaceName,
+};
+
+use crate::{
+    proxy::{
+        Proxy, DESKTOP_DESTINATION, DESKTOP_PATH, DOCUMENTS_DESTINATION, DOCUMENTS_PATH,
+        FLATPAK_DESTINATION, FLATPAK_PATH,
+    },
+    Error,
+};
+
+/// A portal interface found by [`introspect`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PortalInterface {
+    name: String,
+    version: u32,
+}
+
+impl PortalInterface {
+    /// The name of the interface, for example `org.freedesktop.portal.Camera`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The version of the interface implemented by the portal.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+}
+
+/// Lists the `org.freedesktop.portal.*` interfaces available on the session
+/// bus along with their versions, sorted by name.
+///
+/// The desktop portals, the documents portal and the Flatpak portal are looked
+/// up. Those that are not running and can't be activated are left out, which
+/// makes the list empty when no portals frontend is installed.
+///
+/// ```rust,no_run
+/// async fn run() -> ashpd::Result<()> {
+///     for portal in ashpd::introspect().await? {
+///         println!("{} version {}", portal.name(), portal.version());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn introspect() -> Result<Vec<PortalInterface>, Error> {
+    let connection = Proxy::connection().await?;
+    let mut portals = Vec::new();
+    for (destination, path) in [
+        (DESKTOP_DESTINATION, DESKTOP_PATH),
+        (DOCUMENTS_DESTINATION, DOCUMENTS_PATH),
+        (FLATPAK_DESTINATION, FLATPAK_PATH),
+    ] {
+        let xml = match IntrospectableProxy::builder(&connection)
+            .destination(destination)?
+            .path(path)?
+            .build()
+            .await?
+            .introspect()
+            .await
+        {
+            Ok(xml) => xml,
+            Err(
+                zbus::fdo::Error::ServiceUnknown(_)
+                | zbus::fdo::Error::NameHasNoOwner(_)
+                | zbus::fdo::Error::UnknownObject(_),
+            ) => continue,
+            Err(err) => return Err(err.into()),
+        };
+        let properties = PropertiesProxy::builder(&connection)
+            .destination(destination)?
+            .path(path)?
+            .build()
+            .await?;
+        for name in interface_names(&xml) {
+            if !name.starts_with("org.freedesktop.portal.") {
+                continue;
+            }
+            let Ok(interface) = InterfaceName::try_from(name) else {
+                continue;
+            };
+            // Interfaces without a version property are at their first version.
+            let version = match properties.get(interface, "version").await {
+                Ok(version) => u32::try_from(version).unwrap_or(1),
+                Err(_) => 1,
+            };
+            portals.push(PortalInterface {
+                name: name.to_owned(),
+                version,
+            });
+        }
+    }
+    portals.sort();
+    Ok(portals)
+}
+
+/// The names of the interfaces of an introspection XML document.
+fn interface_names(xml: &str) -> impl Iterator<Item = &str> {
+    xml.split("<interface").skip(1).filter_map(|element| {
+        let (_, rest) = element.split_once("name=")?;
+        let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let (name, _) = rest[1..].split_once(quote)?;
+        Some(name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn introspection_interface_names() {
+        let xml = r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node>
+  <interface name="org.freedesktop.DBus.Properties">
+    <method name="Get">
+      <arg type="s" name="interface_name" direction="in"/>
+    </method>
+  </interface>
+  <interface name="org.freedesktop.portal.Camera">
+    <property type="u" name="version" access="read"/>
+  </interface>
+  <interface name='org.freedesktop.portal.Trash'/>
+  <node name="request"/>
+</node>"#;
+        assert_eq!(
+            interface_names(xml).collect::<Vec<_>>(),
+            [
+                "org.freedesktop.DBus.Properties",
+                "org.freedesktop.portal.Camera",
+                "org.freedesktop.portal.Trash"
+            ]
+        );
+    }
+}
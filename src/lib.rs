@@ -9,38 +9,51 @@
 #[cfg(all(all(feature = "tokio", feature = "async-std"), not(doc)))]
 compile_error!("You can't enable both async-std & tokio features at once");
 
+// The derive macros refer to the crate as `::ashpd`.
+extern crate self as ashpd;
+
 /// Alias for a [`Result`] with the error type `ashpd::Error`.
 pub type Result<T> = std::result::Result<T, Error>;
 
 static IS_SANDBOXED: OnceLock<bool> = OnceLock::new();
 
 mod activation_token;
+#[cfg(feature = "bevy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bevy")))]
+pub mod bevy;
 /// Interact with the user's desktop such as taking a screenshot, setting a
 /// background or querying the user's location.
 pub mod desktop;
 /// Interact with the documents store or transfer files across apps.
 pub mod documents;
+#[cfg(feature = "debug-dump")]
+mod dump;
 mod error;
+#[cfg(any(test, feature = "mock"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+pub mod mock;
+pub mod prelude;
 mod window_identifier;
 
-pub use self::{activation_token::ActivationToken, window_identifier::WindowIdentifier};
+pub use self::{
+    activation_token::ActivationToken,
+    window_identifier::{WindowIdentifier, WindowIdentifierExporter, WindowIdentifierType},
+};
 mod app_id;
 pub use self::app_id::AppID;
 mod file_path;
 pub use self::file_path::FilePath;
 
 mod proxy;
+pub use proxy::{is_available, set_session_connection, Portal};
 #[cfg(feature = "backend")]
 #[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
-#[allow(missing_docs)]
 pub use proxy::{
-    DESKTOP_DESTINATION, DESKTOP_PATH, DOCUMENTS_DESTINATION, DOCUMENTS_PATH, FLATPAK_DESTINATION,
-    FLATPAK_DEVELOPMENT_DESTINATION, FLATPAK_DEVELOPMENT_PATH, FLATPAK_PATH,
+    BACKGROUND_MONITOR_DESTINATION, BACKGROUND_MONITOR_PATH, DESKTOP_DESTINATION, DESKTOP_PATH,
+    DOCUMENTS_DESTINATION, DOCUMENTS_PATH, FLATPAK_DESTINATION, FLATPAK_DEVELOPMENT_DESTINATION,
+    FLATPAK_DEVELOPMENT_PATH, FLATPAK_PATH, PERMISSION_STORE_DESTINATION, PERMISSION_STORE_PATH,
 };
 
-#[cfg(feature = "backend")]
-#[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
-pub use self::window_identifier::WindowIdentifierType;
 #[cfg(feature = "backend")]
 #[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
 #[allow(missing_docs)]
@@ -50,13 +63,18 @@ pub mod backend;
 /// received an update & install it.
 pub mod flatpak;
 mod helpers;
+mod introspect;
 use std::sync::OnceLock;
 
 #[cfg(feature = "backend")]
 #[cfg_attr(docsrs, doc(cfg(feature = "backend")))]
 pub use async_trait;
 pub use enumflags2;
+pub use introspect::{introspect, PortalInterface};
 pub use url;
+#[cfg(feature = "xkbcommon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "xkbcommon")))]
+pub use xkbcommon;
 pub use zbus::{self, zvariant};
 
 /// Check whether the application is running inside a sandbox.
@@ -78,4 +96,65 @@ pub async fn is_sandboxed() -> bool {
     new_value
 }
 
+/// Run a portal request, giving up after `duration`.
+///
+/// This is useful in environments with no portal UI, where a request could
+/// otherwise hang forever. When the timeout is exceeded, the request future is
+/// dropped, which closes the underlying [`Request`](desktop::Request) object,
+/// and [`Error::TimedOut`] is returned.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use ashpd::desktop::screenshot::Screenshot;
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let request = ashpd::timeout(Duration::from_secs(30), Screenshot::request().send()).await?;
+///     println!("URI: {}", request.response()?.uri());
+///     Ok(())
+/// }
+/// ```
+pub async fn timeout<T>(
+    duration: std::time::Duration,
+    future: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    crate::helpers::timeout(duration, future)
+        .await
+        .unwrap_or(Err(Error::TimedOut))
+}
+
+#[cfg(feature = "block_on")]
+#[cfg_attr(docsrs, doc(cfg(feature = "block_on")))]
+/// Run a future, typically a portal call, to completion on the current thread.
+///
+/// This is only a helper to drive the asynchronous portal APIs from
+/// synchronous code, like command line tools, there are no blocking variants
+/// of the portals. It relies on the same executor as [`zbus::blocking`], so no
+/// extra runtime is pulled in. Signal streams can be consumed the same way, by
+/// calling [`block_on`] on each of their next items.
+///
+/// **Note** With the `tokio` feature, the futures are run on a dedicated
+/// single-threaded runtime. Calling [`block_on`] from within a Tokio runtime
+/// panics, use the async APIs directly there instead.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::account::UserInformation;
+///
+/// fn main() -> ashpd::Result<()> {
+///     let response = ashpd::block_on(async {
+///         UserInformation::request()
+///             .reason("App would like to access user information")
+///             .send()
+///             .await?
+///             .response()
+///     })?;
+///
+///     println!("Name: {:?}", response.name());
+///     Ok(())
+/// }
+/// ```
+pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    zbus::block_on(future)
+}
+
 pub use self::error::{Error, PortalError};
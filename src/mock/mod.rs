@@ -0,0 +1,287 @@
+//! Mock portals, to unit test the portal flows of an application without a
+//! running xdg-desktop-portal.
+//!
+//! A [`MockBus`] starts a private bus with `dbus-daemon`, which has to be
+//! installed, and serves the mock implementations of the portal interfaces
+//! under the well-known name of the desktop portals. The portals are then
+//! created with the connection returned by [`MockBus::connect`], either by
+//! passing it to their `with_connection` constructor and to the `connection`
+//! setter of the request builders, or by sharing it with
+//! [`set_session_connection`](crate::set_session_connection).
+//!
+//! ```rust,no_run
+//! use ashpd::{
+//!     desktop::screenshot::Screenshot,
+//!     mock::{self, MockBus},
+//! };
+//!
+//! async fn run() -> ashpd::Result<()> {
+//!     let uri = url::Url::parse("file:///screenshot.png").unwrap();
+//!     let mut bus = MockBus::new()?;
+//!     bus.serve(mock::Screenshot::new(uri.clone())).await?;
+//!
+//!     let response = Screenshot::request()
+//!         .connection(bus.connect().await?)
+//!         .send()
+//!         .await?
+//!         .response()?;
+//!     assert_eq!(response.uri(), &uri);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The other portals are mocked by serving a [`zbus::interface`]
+//! implementation. The requests are answered with [`respond`] on the object
+//! path returned by [`request_path`]:
+//!
+//! ```rust,no_run
+//! use std::collections::HashMap;
+//!
+//! use ashpd::{
+//!     desktop::ResponseType,
+//!     mock,
+//!     zbus::{
+//!         self, interface,
+//!         message::Header,
+//!         zvariant::{OwnedObjectPath, OwnedValue, Value},
+//!     },
+//! };
+//!
+//! struct Account;
+//!
+//! #[interface(name = "org.freedesktop.portal.Account")]
+//! impl Account {
+//!     async fn get_user_information(
+//!         &self,
+//!         #[zbus(header)] header: Header<'_>,
+//!         #[zbus(connection)] connection: &zbus::Connection,
+//!         _window: &str,
+//!         options: HashMap<String, OwnedValue>,
+//!     ) -> zbus::fdo::Result<OwnedObjectPath> {
+//!         let path = mock::request_path(&header, &options)?;
+//!         let results = HashMap::from([
+//!             ("id", Value::from("jane")),
+//!             ("name", Value::from("Jane Doe")),
+//!             ("image", Value::from("file:///jane.png")),
+//!         ]);
+//!         mock::respond(connection, &path, ResponseType::Success, &results).await?;
+//!         Ok(path)
+//!     }
+//! }
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader},
+    process::{Child, Command, Stdio},
+};
+
+use serde::Serialize;
+use zbus::{
+    message::Header,
+    object_server::Interface,
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type},
+};
+
+use crate::{
+    desktop::ResponseType,
+    proxy::{DESKTOP_DESTINATION, DESKTOP_PATH},
+    Error,
+};
+
+mod screenshot;
+
+pub use screenshot::Screenshot;
+
+/// A private bus started with `dbus-daemon`, serving mock portals.
+///
+/// The daemon is killed when dropped.
+#[derive(Debug)]
+pub struct MockBus {
+    daemon: Child,
+    address: String,
+    portals: Option<zbus::Connection>,
+}
+
+impl MockBus {
+    /// Starts the bus.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `dbus-daemon` is not installed or doesn't print the address
+    /// of the bus.
+    pub fn new() -> io::Result<Self> {
+        let daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--nopidfile", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut bus = Self {
+            daemon,
+            address: String::new(),
+            portals: None,
+        };
+        let stdout = bus.daemon.stdout.take().ok_or(io::ErrorKind::BrokenPipe)?;
+        BufReader::new(stdout).read_line(&mut bus.address)?;
+        bus.address.truncate(bus.address.trim_end().len());
+        if bus.address.is_empty() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(bus)
+    }
+
+    /// The address of the bus.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Opens a new connection to the bus, to create the portals with.
+    pub async fn connect(&self) -> Result<zbus::Connection, Error> {
+        Ok(zbus::connection::Builder::address(self.address())?
+            .build()
+            .await?)
+    }
+
+    /// Serves `portal` at the object path of the desktop portals.
+    ///
+    /// The connection serving the portals owns the well-known name of the
+    /// desktop portals once the first one is served.
+    pub async fn serve<I: Interface>(&mut self, portal: I) -> Result<(), Error> {
+        let portals = match &self.portals {
+            Some(portals) => portals,
+            None => {
+                let portals = zbus::connection::Builder::address(self.address())?
+                    .name(DESKTOP_DESTINATION)?
+                    .build()
+                    .await?;
+                self.portals.insert(portals)
+            }
+        };
+        portals.object_server().at(DESKTOP_PATH, portal).await?;
+        Ok(())
+    }
+}
+
+impl Drop for MockBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+/// The object path of the request made by the sender of `header` with the
+/// `handle_token` of its `options`, which is the path its response has to be
+/// sent to.
+///
+/// # Errors
+///
+/// Fails when the message has no sender or the options have no handle token.
+pub fn request_path(
+    header: &Header<'_>,
+    options: &HashMap<String, OwnedValue>,
+) -> zbus::fdo::Result<OwnedObjectPath> {
+    let sender = header
+        .sender()
+        .ok_or_else(|| zbus::fdo::Error::InvalidArgs("The call has no sender".to_owned()))?;
+    let token = options
+        .get("handle_token")
+        .and_then(|token| token.downcast_ref::<&str>().ok())
+        .ok_or_else(|| zbus::fdo::Error::InvalidArgs("The handle token is missing".to_owned()))?;
+    let sender = sender.trim_start_matches(':').replace('.', "_");
+    ObjectPath::try_from(format!("{DESKTOP_PATH}/request/{sender}/{token}"))
+        .map(Into::into)
+        .map_err(|err| zbus::fdo::Error::InvalidArgs(err.to_string()))
+}
+
+/// Sends the response of the request at `path`, with the `results`
+/// serialized as a dictionary.
+///
+/// The requests are subscribed to before being made, so they can be answered
+/// before the method call returns.
+pub async fn respond<R>(
+    connection: &zbus::Connection,
+    path: &ObjectPath<'_>,
+    response: ResponseType,
+    results: &R,
+) -> zbus::Result<()>
+where
+    R: Serialize + Type,
+{
+    connection
+        .emit_signal(
+            None::<()>,
+            path,
+            "org.freedesktop.portal.Request",
+            "Response",
+            &(response, results),
+        )
+        .await
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use super::*;
+    use crate::desktop::{request::ResponseError, screenshot, Color};
+
+    #[test]
+    fn screenshot_fixture() {
+        let Ok(mut bus) = MockBus::new() else {
+            // No bus to run the test against.
+            return;
+        };
+        let uri = url::Url::parse("file:///screenshot.png").unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            bus.serve(Screenshot::new(uri.clone()).color(Color::new(0.0, 0.5, 1.0)))
+                .await
+                .unwrap();
+            let connection = bus.connect().await.unwrap();
+
+            let response = screenshot::Screenshot::request()
+                .connection(connection.clone())
+                .send()
+                .await
+                .unwrap()
+                .response()
+                .unwrap();
+            assert_eq!(response.uri(), &uri);
+            let color = Color::pick()
+                .connection(connection)
+                .send_and_wait()
+                .await
+                .unwrap();
+            assert_eq!(color, Color::new(0.0, 0.5, 1.0));
+        });
+    }
+
+    #[test]
+    fn cancelled_screenshot() {
+        let Ok(mut bus) = MockBus::new() else {
+            return;
+        };
+        let uri = url::Url::parse("file:///screenshot.png").unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            bus.serve(Screenshot::new(uri).cancelled()).await.unwrap();
+            let connection = bus.connect().await.unwrap();
+
+            let request = screenshot::Screenshot::request()
+                .connection(connection)
+                .send()
+                .await
+                .unwrap();
+            assert!(matches!(
+                request.response(),
+                Err(crate::Error::Response(ResponseError::Cancelled))
+            ));
+        });
+    }
+}
@@ -0,0 +1,97 @@
+// The property change notifiers generated by `interface` are not documented.
+#![allow(missing_docs)]
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use zbus::{
+    interface,
+    message::Header,
+    zvariant::{OwnedObjectPath, OwnedValue, Type, Value},
+};
+
+use super::{request_path, respond};
+use crate::desktop::{Color, ResponseType};
+
+/// A screenshot portal answering all the requests with the same screenshot
+/// and color.
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    uri: url::Url,
+    color: Color,
+    response: ResponseType,
+}
+
+impl Screenshot {
+    /// Answers the screenshot requests with `uri` and the color requests with
+    /// white.
+    pub fn new(uri: url::Url) -> Self {
+        Self {
+            uri,
+            color: Color::new(1.0, 1.0, 1.0),
+            response: ResponseType::Success,
+        }
+    }
+
+    /// Answers the color requests with `color`.
+    #[must_use]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Answers all the requests as cancelled by the user.
+    #[must_use]
+    pub fn cancelled(mut self) -> Self {
+        self.response = ResponseType::Cancelled;
+        self
+    }
+
+    async fn answer<R>(
+        &self,
+        header: &Header<'_>,
+        connection: &zbus::Connection,
+        options: &HashMap<String, OwnedValue>,
+        results: &R,
+    ) -> zbus::fdo::Result<OwnedObjectPath>
+    where
+        R: Serialize + Type,
+    {
+        let path = request_path(header, options)?;
+        respond(connection, &path, self.response, results).await?;
+        Ok(path)
+    }
+}
+
+#[interface(name = "org.freedesktop.portal.Screenshot")]
+impl Screenshot {
+    /// Answers with the screenshot.
+    async fn screenshot(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        _parent_window: &str,
+        options: HashMap<String, OwnedValue>,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        let results = HashMap::from([("uri", Value::from(self.uri.as_str()))]);
+        self.answer(&header, connection, &options, &results).await
+    }
+
+    /// Answers with the color.
+    async fn pick_color(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &zbus::Connection,
+        _parent_window: &str,
+        options: HashMap<String, OwnedValue>,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.answer(&header, connection, &options, &self.color)
+            .await
+    }
+
+    /// The version of the interface.
+    #[zbus(property, name = "version")]
+    fn version(&self) -> u32 {
+        2
+    }
+}
@@ -0,0 +1,22 @@
+//! The types most portal calls need, to be glob imported.
+//!
+//! ```rust,no_run
+//! use ashpd::prelude::*;
+//!
+//! async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//!     let uri = Url::parse("https://github.com/bilelmoussaoui/ashpd")?;
+//!     ashpd::desktop::open_uri::open(&uri).await?;
+//!
+//!     let screenshot = ashpd::desktop::screenshot::take().await?;
+//!     println!("{}", screenshot.uri());
+//!     Ok(())
+//! }
+//! ```
+
+pub use enumflags2::BitFlags;
+pub use url::Url;
+
+pub use crate::{
+    desktop::{PersistMode, Request, Session},
+    ActivationToken, Error, FilePath, PortalError, WindowIdentifier,
+};
@@ -2,29 +2,213 @@ use std::{fmt::Debug, future::ready, ops::Deref, sync::OnceLock};
 
 use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use zbus::zvariant::{ObjectPath, OwnedValue, Type};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type};
 #[cfg(feature = "tracing")]
 use zbus::Message;
 
 use crate::{
     desktop::{HandleToken, Request},
-    Error, PortalError,
+    Error,
 };
 
+/// The well-known name of the desktop portals frontend.
 pub const DESKTOP_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+/// The object path of the desktop portals.
 pub const DESKTOP_PATH: &str = "/org/freedesktop/portal/desktop";
 
+/// The well-known name of the documents portal.
 pub const DOCUMENTS_DESTINATION: &str = "org.freedesktop.portal.Documents";
+/// The object path of the documents portal.
 pub const DOCUMENTS_PATH: &str = "/org/freedesktop/portal/documents";
 
+/// The well-known name of the background apps monitor.
+pub const BACKGROUND_MONITOR_DESTINATION: &str = "org.freedesktop.background.Monitor";
+/// The object path of the background apps monitor.
+pub const BACKGROUND_MONITOR_PATH: &str = "/org/freedesktop/background/monitor";
+
+/// The well-known name of the permission store.
+pub const PERMISSION_STORE_DESTINATION: &str = "org.freedesktop.impl.portal.PermissionStore";
+/// The object path of the permission store.
+pub const PERMISSION_STORE_PATH: &str = "/org/freedesktop/impl/portal/PermissionStore";
+
+/// The well-known name of the Flatpak portal.
 pub const FLATPAK_DESTINATION: &str = "org.freedesktop.portal.Flatpak";
+/// The object path of the Flatpak portal.
 pub const FLATPAK_PATH: &str = "/org/freedesktop/portal/Flatpak";
 
+/// The well-known name of the Flatpak development interface.
 pub const FLATPAK_DEVELOPMENT_DESTINATION: &str = "org.freedesktop.Flatpak";
+/// The object path of the Flatpak development interface.
 pub const FLATPAK_DEVELOPMENT_PATH: &str = "/org/freedesktop/Flatpak/Development";
 
 static SESSION: OnceLock<zbus::Connection> = OnceLock::new();
 
+/// Use `connection` as the session bus connection shared by all the portals.
+///
+/// By default, a connection to the session bus is lazily opened the first
+/// time a portal is used and reused afterwards. Calling this function before
+/// that allows to share a connection the application already maintains, or to
+/// connect to a custom bus address. To only use a connection for some
+/// portals, create them with their `with_connection` constructor instead, like
+/// [`Settings::with_connection`](crate::desktop::settings::Settings::with_connection),
+/// or pass it to the `connection` setter of the request builders.
+///
+/// The tasks the crate spawns in the background, like closing the requests
+/// whose future was dropped, run on the executor of the connection. Building
+/// it with [`internal_executor(false)`](zbus::connection::Builder::internal_executor)
+/// lets the application drive them with
+/// [`Executor::tick`](zbus::Executor::tick) from its own event loop, for
+/// example a glib main context.
+///
+/// This can also be used to unit test portal flows without a running
+/// xdg-desktop-portal, by serving mock implementations of the portal
+/// interfaces on a private bus. The `mock` feature provides `ashpd::mock` to
+/// start the bus and serve them:
+///
+/// ```rust,no_run
+/// use ashpd::desktop::settings::{ColorScheme, Settings};
+/// use zbus::{interface, zvariant::OwnedValue};
+///
+/// struct MockSettings;
+///
+/// #[interface(name = "org.freedesktop.portal.Settings")]
+/// impl MockSettings {
+///     #[zbus(property, name = "version")]
+///     fn version(&self) -> u32 {
+///         2
+///     }
+///
+///     async fn read(&self, namespace: &str, key: &str) -> zbus::fdo::Result<OwnedValue> {
+///         match (namespace, key) {
+///             ("org.freedesktop.appearance", "color-scheme") => Ok(OwnedValue::from(1u32)),
+///             _ => Err(zbus::fdo::Error::Failed("Unknown setting".to_owned())),
+///         }
+///     }
+/// }
+///
+/// async fn run() -> ashpd::Result<()> {
+///     // A private bus, started with `dbus-daemon --session --print-address`
+///     let connection = zbus::connection::Builder::address("unix:path=/tmp/test-bus")?
+///         .name("org.freedesktop.portal.Desktop")?
+///         .serve_at("/org/freedesktop/portal/desktop", MockSettings)?
+///         .build()
+///         .await?;
+///     ashpd::set_session_connection(connection).unwrap();
+///
+///     let settings = Settings::new().await?;
+///     assert_eq!(settings.color_scheme().await?, ColorScheme::PreferDark);
+///     Ok(())
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns back `connection` if the shared connection was already
+/// initialized.
+pub fn set_session_connection(connection: zbus::Connection) -> Result<(), zbus::Connection> {
+    SESSION.set(connection)
+}
+
+/// A portal interface, implemented by the wrappers of the portals and by the
+/// request builders of the portals that are only used through requests.
+///
+/// The portals are used over the connection to the session bus shared by all
+/// of them, which can be replaced with [`set_session_connection`]. Their
+/// `with_connection` constructor, and the `connection` setter of the request
+/// builders, use another connection for a single portal instead.
+pub trait Portal {
+    /// The name of the interface, for example `org.freedesktop.portal.Camera`.
+    const INTERFACE: &'static str;
+    /// The well-known name of the service implementing the interface.
+    const DESTINATION: &'static str;
+    /// The object path the interface is implemented at.
+    const PATH: &'static str;
+}
+
+/// Whether the portal `P` is available on the session bus.
+///
+/// Unlike creating the portal, which fails with [`Error::PortalNotFound`], a
+/// missing portal isn't an error and the features relying on it can be hidden
+/// instead. The version of an available portal is only known once it is
+/// created.
+///
+/// ```rust,no_run
+/// use ashpd::desktop::{camera::Camera, screenshot::ScreenshotRequest};
+///
+/// async fn run() -> ashpd::Result<()> {
+///     if ashpd::is_available::<Camera>().await? {
+///         println!("The camera can be requested");
+///     }
+///     if !ashpd::is_available::<ScreenshotRequest>().await? {
+///         println!("Screenshots are not supported");
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn is_available<P: Portal>() -> Result<bool, Error> {
+    let connection = Proxy::connection().await?;
+    match Proxy::new(connection, P::INTERFACE, P::PATH, P::DESTINATION).await {
+        Ok(_) => Ok(true),
+        Err(Error::PortalNotFound(_)) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Implements [`Portal`] and the `version`, `inner` and `connection` accessors
+/// shared by the wrappers of the portal proxies, which wrap a [`Proxy`] in
+/// their first field.
+macro_rules! impl_portal {
+    ($wrapper:ident, $interface:literal) => {
+        $crate::proxy::impl_portal!($wrapper, $interface, DESKTOP_DESTINATION, DESKTOP_PATH);
+    };
+    ($wrapper:ident, $interface:literal, $destination:ident, $path:ident) => {
+        impl $crate::proxy::Portal for $wrapper<'_> {
+            const INTERFACE: &'static str = $interface;
+            const DESTINATION: &'static str = $crate::proxy::$destination;
+            const PATH: &'static str = $crate::proxy::$path;
+        }
+
+        $crate::proxy::impl_portal!($wrapper);
+    };
+    // The wrappers of the objects created at runtime, which are not looked up.
+    ($wrapper:ident) => {
+        impl<'a> $wrapper<'a> {
+            /// Returns the version of the interface implemented by the portal.
+            ///
+            /// Methods and properties introduced in a later version of the
+            /// interface fail with
+            /// [`Error::RequiresVersion`](crate::Error::RequiresVersion) instead.
+            pub fn version(&self) -> u32 {
+                self.0.version()
+            }
+
+            /// The underlying proxy, to issue raw calls or watch the properties
+            /// that are not wrapped.
+            pub fn inner(&self) -> &zbus::Proxy<'a> {
+                &self.0
+            }
+
+            /// The connection the portal is used over, which can be shared with
+            /// the other D-Bus work of the application.
+            pub fn connection(&self) -> &zbus::Connection {
+                self.0.connection()
+            }
+        }
+    };
+    // The request builders of the portals that have no public wrapper.
+    (request $($builder:ident),+ => $interface:literal) => {
+        $(
+            impl $crate::proxy::Portal for $builder {
+                const INTERFACE: &'static str = $interface;
+                const DESTINATION: &'static str = $crate::proxy::DESKTOP_DESTINATION;
+                const PATH: &'static str = $crate::proxy::DESKTOP_PATH;
+            }
+        )+
+    };
+}
+
+pub(crate) use impl_portal;
+
 #[derive(Debug)]
 pub struct Proxy<'a> {
     inner: zbus::Proxy<'a>,
@@ -42,18 +226,37 @@ impl<'a> Proxy<'a> {
         }
     }
 
+    pub(crate) async fn connection_or_shared(
+        connection: Option<zbus::Connection>,
+    ) -> zbus::Result<zbus::Connection> {
+        match connection {
+            Some(connection) => Ok(connection),
+            None => Self::connection().await,
+        }
+    }
+
     pub async fn unique_name(
+        connection: &zbus::Connection,
         prefix: &str,
         handle_token: &HandleToken,
     ) -> Result<ObjectPath<'static>, Error> {
-        let connection = Self::connection().await?;
-        let unique_name = connection.unique_name().unwrap();
-        let unique_identifier = unique_name.trim_start_matches(':').replace('.', "_");
+        let unique_identifier = Self::unique_identifier(connection)?;
         ObjectPath::try_from(format!("{prefix}/{unique_identifier}/{handle_token}"))
             .map_err(From::from)
     }
 
+    /// The unique name of the connection, the way the portals use it in the
+    /// request and session paths.
+    pub fn unique_identifier(connection: &zbus::Connection) -> Result<String, Error> {
+        // Peer to peer connections don't get a unique name from a bus.
+        let unique_name = connection
+            .unique_name()
+            .ok_or_else(|| zbus::Error::Failure("The connection has no unique name".to_owned()))?;
+        Ok(unique_name.trim_start_matches(':').replace('.', "_"))
+    }
+
     pub async fn new<P>(
+        connection: zbus::Connection,
         interface: &'a str,
         path: P,
         destination: &'a str,
@@ -62,7 +265,6 @@ impl<'a> Proxy<'a> {
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
-        let connection = Self::connection().await?;
         let inner: zbus::Proxy = zbus::ProxyBuilder::new(&connection)
             .interface(interface)?
             .path(path)?
@@ -70,6 +272,12 @@ impl<'a> Proxy<'a> {
             .build()
             .await?;
 
+        // We are sure it is a valid interface name, should fix the type system here
+        let not_found = || {
+            crate::Error::PortalNotFound(
+                zbus::names::OwnedInterfaceName::try_from(interface).unwrap(),
+            )
+        };
         let version = match inner
             .get_property::<u32>("version")
             .await
@@ -78,50 +286,114 @@ impl<'a> Proxy<'a> {
             Ok(v) => Ok(v),
             Err(zbus::fdo::Error::InvalidArgs(details)) => {
                 if details.contains(interface) {
-                    Err(crate::Error::PortalNotFound(
-                        // We are sure it is a valid interface name, should fix the type system
-                        // here
-                        zbus::names::OwnedInterfaceName::try_from(interface).unwrap(),
-                    ))
+                    Err(not_found())
                 } else {
                     Ok(1)
                 }
             }
+            // Nothing owns the destination name and it can't be activated, or
+            // it doesn't implement the interface.
+            Err(
+                zbus::fdo::Error::ServiceUnknown(_)
+                | zbus::fdo::Error::NameHasNoOwner(_)
+                | zbus::fdo::Error::UnknownObject(_)
+                | zbus::fdo::Error::UnknownInterface(_),
+            ) => Err(not_found()),
             _ => Ok(1),
         }?;
         Ok(Self { inner, version })
     }
 
-    pub async fn new_desktop_with_path<P>(interface: &'a str, path: P) -> Result<Proxy<'a>, Error>
+    /// Create a proxy for an object created by a portal call, a request or a
+    /// session.
+    ///
+    /// The proxy is created before the call, to receive the signals of the
+    /// object from the start, so the object doesn't exist yet and its version
+    /// can't be looked up.
+    pub(crate) async fn new_desktop_object<P>(
+        connection: zbus::Connection,
+        interface: &'a str,
+        path: P,
+    ) -> Result<Proxy<'a>, Error>
     where
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
-        Self::new(interface, path, DESKTOP_DESTINATION).await
+        let inner: zbus::Proxy = zbus::ProxyBuilder::new(&connection)
+            .interface(interface)?
+            .path(path)?
+            .destination(DESKTOP_DESTINATION)?
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .await?;
+        Ok(Self { inner, version: 1 })
+    }
+
+    pub async fn new_desktop(
+        connection: zbus::Connection,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
+        Self::new(connection, interface, DESKTOP_PATH, DESKTOP_DESTINATION).await
+    }
+
+    pub async fn new_documents(
+        connection: zbus::Connection,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
+        Self::new(connection, interface, DOCUMENTS_PATH, DOCUMENTS_DESTINATION).await
     }
 
-    pub async fn new_desktop(interface: &'a str) -> Result<Proxy<'a>, Error> {
-        Self::new(interface, DESKTOP_PATH, DESKTOP_DESTINATION).await
+    pub async fn new_background_monitor(
+        connection: zbus::Connection,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
+        Self::new(
+            connection,
+            interface,
+            BACKGROUND_MONITOR_PATH,
+            BACKGROUND_MONITOR_DESTINATION,
+        )
+        .await
     }
 
-    pub async fn new_documents(interface: &'a str) -> Result<Proxy<'a>, Error> {
-        Self::new(interface, DOCUMENTS_PATH, DOCUMENTS_DESTINATION).await
+    pub async fn new_permission_store(
+        connection: zbus::Connection,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
+        Self::new(
+            connection,
+            interface,
+            PERMISSION_STORE_PATH,
+            PERMISSION_STORE_DESTINATION,
+        )
+        .await
     }
 
-    pub async fn new_flatpak(interface: &'a str) -> Result<Proxy<'a>, Error> {
-        Self::new(interface, FLATPAK_PATH, FLATPAK_DESTINATION).await
+    pub async fn new_flatpak(
+        connection: zbus::Connection,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
+        Self::new(connection, interface, FLATPAK_PATH, FLATPAK_DESTINATION).await
     }
 
-    pub async fn new_flatpak_with_path<P>(interface: &'a str, path: P) -> Result<Proxy<'a>, Error>
+    pub async fn new_flatpak_with_path<P>(
+        connection: zbus::Connection,
+        interface: &'a str,
+        path: P,
+    ) -> Result<Proxy<'a>, Error>
     where
         P: TryInto<ObjectPath<'a>>,
         P::Error: Into<zbus::Error>,
     {
-        Self::new(interface, path, FLATPAK_DESTINATION).await
+        Self::new(connection, interface, path, FLATPAK_DESTINATION).await
     }
 
-    pub async fn new_flatpak_development(interface: &'a str) -> Result<Proxy<'a>, Error> {
+    pub async fn new_flatpak_development(
+        connection: zbus::Connection,
+        interface: &'a str,
+    ) -> Result<Proxy<'a>, Error> {
         Self::new(
+            connection,
             interface,
             FLATPAK_DEVELOPMENT_PATH,
             FLATPAK_DEVELOPMENT_DESTINATION,
@@ -138,12 +410,30 @@ impl<'a> Proxy<'a> {
     where
         T: for<'de> Deserialize<'de> + Type + Debug,
     {
-        let mut request = Request::from_unique_name(handle_token).await?;
-        futures_util::try_join!(request.prepare_response(), async {
-            self.call_method(method_name, &body)
-                .await
-                .map_err(From::from)
-        })?;
+        let mut request = Request::from_unique_name(self.connection(), handle_token).await?;
+        // Dismiss the dialog if the caller drops the future before a response
+        let mut close_guard = request.close_on_drop();
+        #[cfg(feature = "debug-dump")]
+        crate::dump::call(self.interface(), method_name, &body);
+        let handle = match self.call_method(method_name, &body).await {
+            Ok(reply) => reply.body().deserialize::<OwnedObjectPath>()?,
+            Err(err) => {
+                close_guard.disarm();
+                return Err(err.into());
+            }
+        };
+        // The portals older than 0.9 ignore the handle token, the response is
+        // then only emitted on the path they return.
+        if handle.as_ref() != *request.path() {
+            #[cfg(feature = "tracing")]
+            tracing::info!("The request handle is {} instead", handle);
+            close_guard.disarm();
+            request.set_path(handle.into()).await?;
+            close_guard = request.close_on_drop();
+        }
+        let result = request.prepare_response().await;
+        close_guard.disarm();
+        result?;
         Ok(request)
     }
 
@@ -174,15 +464,34 @@ impl<'a> Proxy<'a> {
             tracing::info!("Calling method {}:{}", self.interface(), method_name);
             tracing::debug!("With body {:#?}", body);
         }
-        let msg = self
-            .call_method(method_name, &body)
-            .await
-            .map_err::<PortalError, _>(From::from)?;
+        #[cfg(feature = "debug-dump")]
+        crate::dump::call(self.interface(), method_name, &body);
+        let msg = self.call_method(method_name, &body).await?;
+        #[cfg(feature = "debug-dump")]
+        crate::dump::message("reply", self.interface(), method_name, &msg);
         let reply = msg.body().deserialize::<R>()?;
 
         Ok(reply)
     }
 
+    /// Like [`Proxy::call`] but without waiting for the reply, nor getting
+    /// the errors of the portal.
+    pub(crate) async fn call_noreply(
+        &self,
+        method_name: &'static str,
+        body: impl Serialize + Type + Debug,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::info!("Calling method {}:{}", self.interface(), method_name);
+            tracing::debug!("With body {:#?}", body);
+        }
+        #[cfg(feature = "debug-dump")]
+        crate::dump::call(self.interface(), method_name, &body);
+        self.inner.call_noreply(method_name, &body).await?;
+        Ok(())
+    }
+
     pub(crate) async fn call_versioned<R>(
         &self,
         method_name: &'static str,
@@ -228,11 +537,27 @@ impl<'a> Proxy<'a> {
         }
     }
 
+    pub(crate) async fn receive_property_changed<T>(
+        &self,
+        property_name: &'static str,
+    ) -> impl Stream<Item = T> + Unpin + 'a
+    where
+        T: TryFrom<OwnedValue> + Unpin + 'a,
+        zbus::Error: From<<T as TryFrom<OwnedValue>>::Error>,
+    {
+        let stream = self
+            .inner
+            .receive_property_changed::<T>(property_name)
+            .await
+            .filter_map(|changed| async move { changed.get().await.ok() });
+        Box::pin(stream)
+    }
+
     pub(crate) async fn signal_with_args<I>(
         &self,
         name: &'static str,
         args: &[(u8, &str)],
-    ) -> Result<impl Stream<Item = I>, Error>
+    ) -> Result<impl Stream<Item = I> + Unpin, Error>
     where
         I: for<'de> Deserialize<'de> + Type + Debug,
     {
@@ -253,7 +578,10 @@ impl<'a> Proxy<'a> {
             }))
     }
 
-    pub(crate) async fn signal<I>(&self, name: &'static str) -> Result<impl Stream<Item = I>, Error>
+    pub(crate) async fn signal<I>(
+        &self,
+        name: &'static str,
+    ) -> Result<impl Stream<Item = I> + Unpin, Error>
     where
         I: for<'de> Deserialize<'de> + Type + Debug,
     {
@@ -277,6 +605,8 @@ where
     I: for<'de> Deserialize<'de> + Type + Debug,
 {
     tracing::info!("Received signal '{name}' on '{ifc}'");
+    #[cfg(feature = "debug-dump")]
+    crate::dump::message("signal", ifc, name, &msg);
     match msg.body().deserialize() {
         Ok(body) => {
             tracing::debug!("With body {body:#?}");
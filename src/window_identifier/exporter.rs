@@ -0,0 +1,99 @@
+use std::{fmt, future::Future, pin::Pin};
+
+use super::WindowIdentifierType;
+
+/// Exports the windows of a toolkit that isn't supported by ashpd, to create a
+/// [`WindowIdentifier`][`super::WindowIdentifier`] with
+/// [`WindowIdentifier::from_exporter`][`super::WindowIdentifier::from_exporter`].
+///
+/// Under Wayland, the exporter typically uses the
+/// [xdg-foreign](https://gitlab.freedesktop.org/wayland/wayland-protocols/-/blob/main/unstable/xdg-foreign/xdg-foreign-unstable-v2.xml)
+/// protocol to export the surface of the window and revokes the exported
+/// handle when unexporting it. Under X11, the XID of the window is the handle
+/// and there is nothing to unexport.
+///
+/// The returned futures have to be `Send`, so the identifier can be exported
+/// and unexported from a multi-threaded executor.
+///
+/// # Examples
+///
+/// ```rust
+/// use ashpd::{WindowIdentifier, WindowIdentifierExporter, WindowIdentifierType};
+///
+/// struct MyWindow {
+///     handle: String,
+/// }
+///
+/// impl WindowIdentifierExporter for MyWindow {
+///     async fn export(&self) -> Option<WindowIdentifierType> {
+///         // Ask the toolkit to export the surface of the window.
+///         Some(WindowIdentifierType::Wayland(self.handle.clone()))
+///     }
+///
+///     async fn unexport(&self) {
+///         // Ask the toolkit to revoke the exported handle.
+///     }
+/// }
+///
+/// async fn run() -> ashpd::Result<()> {
+///     let window = MyWindow {
+///         handle: "c2e10a7b".to_owned(),
+///     };
+///     let identifier = WindowIdentifier::from_exporter(window).await;
+///     assert_eq!(identifier.to_string(), "wayland:c2e10a7b");
+///
+///     // Open some portals
+///
+///     identifier.unexport().await;
+///     Ok(())
+/// }
+/// ```
+pub trait WindowIdentifierExporter: Send + Sync + 'static {
+    /// Exports the window, returning its handle or `None` if it couldn't be
+    /// exported.
+    fn export(&self) -> impl Future<Output = Option<WindowIdentifierType>> + Send;
+
+    /// Revokes the handle returned by
+    /// [`export`][`WindowIdentifierExporter::export`], once the portal
+    /// dialogs don't need it anymore.
+    fn unexport(&self) -> impl Future<Output = ()> + Send;
+}
+
+/// The object safe part of [`WindowIdentifierExporter`], to store any exporter
+/// in a [`WindowIdentifier`][`super::WindowIdentifier`].
+trait Unexport: Send + Sync {
+    fn unexport(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+}
+
+impl<E: WindowIdentifierExporter> Unexport for E {
+    fn unexport(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(WindowIdentifierExporter::unexport(self))
+    }
+}
+
+pub struct ExportedWindowIdentifier {
+    exporter: Box<dyn Unexport>,
+    type_: WindowIdentifierType,
+}
+
+impl ExportedWindowIdentifier {
+    pub async fn new(exporter: impl WindowIdentifierExporter) -> Option<Self> {
+        let type_ = exporter.export().await?;
+        Some(Self {
+            exporter: Box::new(exporter),
+            type_,
+        })
+    }
+
+    pub async fn unexport(self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("Unexporting handle: {}", self.type_);
+        self.exporter.unexport().await;
+    }
+}
+
+impl fmt::Display for ExportedWindowIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format!("{}", self.type_))
+    }
+}
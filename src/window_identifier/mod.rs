@@ -71,6 +71,34 @@ use zbus::zvariant::Type;
 /// The constructor should return a valid identifier under both X11 and Wayland
 /// and fallback to the [`Default`] implementation otherwise.
 ///
+/// ## With Qt
+///
+/// The feature `qt` must be enabled, which requires the private QtGui headers
+/// to be installed. A [`WindowIdentifier`] can then be created from a
+/// `QWindow` pointer, for example the one returned by `QWidget::windowHandle`,
+/// with `WindowIdentifier::from_qwindow`:
+///
+/// ```rust,ignore
+/// // let window: *mut c_void = widget_window_handle;
+/// let identifier = unsafe { WindowIdentifier::from_qwindow(window) }.await;
+///
+/// /// Open some portals
+/// ```
+///
+/// ## With Slint
+///
+/// The feature `raw_handle` must be enabled, as well as the
+/// `raw-window-handle-06` feature of Slint. The `slint::WindowHandle` of a
+/// window can then be converted with `WindowIdentifier::from_window`, which
+/// exports the surface under Wayland:
+///
+/// ```rust,ignore
+/// let window = app.window();
+/// let identifier = WindowIdentifier::from_window(&window.window_handle()).await;
+///
+/// /// Open some portals
+/// ```
+///
 /// ## Other Toolkits
 ///
 /// If you have access to `RawWindowHandle` you can convert it to a
@@ -83,6 +111,10 @@ use zbus::zvariant::Type;
 /// /// Open some portals
 /// ```
 ///
+/// Otherwise, the toolkit can implement [`WindowIdentifierExporter`] for its
+/// windows and create the [`WindowIdentifier`] with
+/// [`WindowIdentifier::from_exporter`].
+///
 /// In case you don't have access to a WindowIdentifier:
 /// ```rust
 /// use ashpd::WindowIdentifier;
@@ -102,6 +134,8 @@ pub enum WindowIdentifier {
     #[doc(hidden)]
     Wayland(WaylandWindowIdentifier),
     #[doc(hidden)]
+    Exported(ExportedWindowIdentifier),
+    #[doc(hidden)]
     X11(WindowIdentifierType),
     #[doc(hidden)]
     #[default]
@@ -127,6 +161,7 @@ impl std::fmt::Display for WindowIdentifier {
             Self::Gtk4(identifier) => f.write_str(&format!("{identifier}")),
             #[cfg(feature = "wayland")]
             Self::Wayland(identifier) => f.write_str(&format!("{identifier}")),
+            Self::Exported(identifier) => f.write_str(&format!("{identifier}")),
             Self::X11(identifier) => f.write_str(&format!("{identifier}")),
             Self::None => f.write_str(""),
         }
@@ -186,6 +221,124 @@ impl WindowIdentifier {
         }
     }
 
+    #[cfg(feature = "raw_handle")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw_handle")))]
+    /// Create an instance of [`WindowIdentifier`] from a window implementing
+    /// [`HasWindowHandle`](raw_window_handle::HasWindowHandle) and
+    /// [`HasDisplayHandle`](raw_window_handle::HasDisplayHandle), like a
+    /// winit window, the `eframe::Frame` of an egui application or the
+    /// `slint::WindowHandle` of a Slint window.
+    ///
+    /// Under Wayland, the surface of the window is exported, which is why the
+    /// constructor is async. The handles are read right away, the returned
+    /// future doesn't borrow `window` and can be spawned. Fallbacks to the
+    /// [`Default`] implementation if the handles are not available.
+    ///
+    /// ```rust,ignore
+    /// impl eframe::App for MyApp {
+    ///     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+    ///         egui::CentralPanel::default().show(ctx, |ui| {
+    ///             if ui.button("Open…").clicked() {
+    ///                 let identifier = WindowIdentifier::from_window(frame);
+    ///                 // The application runs inside a Tokio runtime.
+    ///                 tokio::spawn(async move {
+    ///                     let request = SelectedFiles::open_file()
+    ///                         .identifier(identifier.await)
+    ///                         .send()
+    ///                         .await;
+    ///                     // Send the selected files back to the UI
+    ///                 });
+    ///             }
+    ///         });
+    ///     }
+    /// }
+    /// ```
+    pub fn from_window<W>(window: &W) -> impl std::future::Future<Output = Self> + Send + 'static
+    where
+        W: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle + ?Sized,
+    {
+        use raw_window_handle::{
+            RawDisplayHandle::Wayland as DisplayHandle,
+            RawWindowHandle::{Wayland, Xcb, Xlib},
+        };
+        let window_handle = window.window_handle().map(|handle| handle.as_raw());
+        let display_handle = window.display_handle().map(|handle| handle.as_raw());
+        // Only owned handles are moved into the future, the raw pointers are
+        // neither `Send` nor `'static`.
+        let (xid, surface) = match (window_handle, display_handle) {
+            (Ok(Wayland(wl_handle)), Ok(DisplayHandle(wl_display))) => (None, unsafe {
+                WaylandWindowIdentifier::surface_from_raw(
+                    wl_handle.surface.as_ptr(),
+                    wl_display.display.as_ptr(),
+                )
+            }),
+            (Ok(Xlib(x_handle)), _) => (Some(x_handle.window), None),
+            (Ok(Xcb(x_handle)), _) => (Some(x_handle.window.get().into()), None),
+            _ => (None, None),
+        };
+        async move {
+            if let Some(xid) = xid {
+                return Self::from_xid(xid);
+            }
+            let Some((conn, surface)) = surface else {
+                return Self::default();
+            };
+            match WaylandWindowIdentifier::new_inner(conn, &surface).await {
+                Some(identifier) => Self::Wayland(identifier),
+                None => Self::default(),
+            }
+        }
+    }
+
+    /// Create an instance of [`WindowIdentifier`] from the window exported by
+    /// `exporter`, for the toolkits that are not supported by ashpd.
+    ///
+    /// Fallbacks to the [`Default`] implementation if the window couldn't be
+    /// exported.
+    pub async fn from_exporter(exporter: impl WindowIdentifierExporter) -> Self {
+        match ExportedWindowIdentifier::new(exporter).await {
+            Some(identifier) => Self::Exported(identifier),
+            None => Self::default(),
+        }
+    }
+
+    /// Revokes the exported handle of a [`WindowIdentifier`] created with
+    /// [`WindowIdentifier::from_exporter`], by awaiting
+    /// [`WindowIdentifierExporter::unexport`].
+    ///
+    /// Dropping such an identifier only drops the exporter. The other
+    /// identifiers are unexported when dropped, this is the same as dropping
+    /// them.
+    pub async fn unexport(self) {
+        if let Self::Exported(identifier) = self {
+            identifier.unexport().await;
+        }
+    }
+
+    #[cfg(feature = "qt")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "qt")))]
+    /// Create an instance of [`WindowIdentifier`] from a `QWindow`.
+    ///
+    /// The constructor returns a valid handle under both Wayland & X11, the
+    /// surface of the window being exported under Wayland, and fallbacks to
+    /// the [`Default`] implementation otherwise, for example when the window
+    /// isn't shown yet.
+    ///
+    /// # Safety
+    ///
+    /// `window` has to be a valid `QWindow` pointer, which outlives the
+    /// returned `WindowIdentifier`. It has to be called from the GUI thread.
+    #[doc(alias = "xdp_parent_new_qt")]
+    pub async unsafe fn from_qwindow(window: *mut std::ffi::c_void) -> Self {
+        match qt::QtWindow::new(window) {
+            Some(qt::QtWindow::X11(xid)) => Self::from_xid(xid),
+            Some(qt::QtWindow::Wayland { surface, display }) => {
+                Self::from_wayland_raw(surface, display).await
+            }
+            None => Self::default(),
+        }
+    }
+
     /// Create an instance of [`WindowIdentifier`] from an X11 window's XID.
     pub fn from_xid(xid: std::os::raw::c_ulong) -> Self {
         Self::X11(WindowIdentifierType::X11(xid))
@@ -312,6 +465,10 @@ impl<'de> Deserialize<'de> for WindowIdentifierType {
     }
 }
 
+mod exporter;
+use self::exporter::ExportedWindowIdentifier;
+pub use self::exporter::WindowIdentifierExporter;
+
 #[cfg(any(feature = "gtk4_wayland", feature = "gtk4_x11"))]
 mod gtk4;
 
@@ -319,6 +476,9 @@ mod gtk4;
 pub use self::gtk4::Gtk4WindowIdentifier;
 use crate::PortalError;
 
+#[cfg(feature = "qt")]
+mod qt;
+
 #[cfg(feature = "wayland")]
 mod wayland;
 
@@ -351,4 +511,52 @@ mod tests {
         assert!(WindowIdentifierType::from_str("some_handle").is_err());
         assert!(WindowIdentifierType::from_str("some_type:some_handle").is_err());
     }
+
+    #[test]
+    fn exporter() {
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+
+        use futures_util::FutureExt;
+
+        use super::WindowIdentifierExporter;
+
+        struct Exporter {
+            handle: Option<&'static str>,
+            unexported: Arc<AtomicBool>,
+        }
+
+        impl WindowIdentifierExporter for Exporter {
+            async fn export(&self) -> Option<WindowIdentifierType> {
+                self.handle
+                    .map(|handle| WindowIdentifierType::Wayland(handle.to_owned()))
+            }
+
+            async fn unexport(&self) {
+                self.unexported.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let unexported = Arc::new(AtomicBool::new(false));
+        let identifier = WindowIdentifier::from_exporter(Exporter {
+            handle: Some("c2e10a7b"),
+            unexported: Arc::clone(&unexported),
+        })
+        .now_or_never()
+        .unwrap();
+        assert_eq!(identifier.to_string(), "wayland:c2e10a7b");
+        assert!(!unexported.load(Ordering::SeqCst));
+        identifier.unexport().now_or_never().unwrap();
+        assert!(unexported.load(Ordering::SeqCst));
+
+        let identifier = WindowIdentifier::from_exporter(Exporter {
+            handle: None,
+            unexported: Arc::new(AtomicBool::new(false)),
+        })
+        .now_or_never()
+        .unwrap();
+        assert_eq!(identifier.to_string(), "");
+    }
 }
@@ -0,0 +1,69 @@
+use std::ffi::c_void;
+
+use cpp::cpp;
+// Links QtGui, and provides the include paths to the build script.
+use qttypes as _;
+
+cpp! {{
+    #include <QtGui/QGuiApplication>
+    #include <QtGui/QWindow>
+    #include <qpa/qplatformnativeinterface.h>
+}}
+
+/// The native handles of a `QWindow`.
+pub enum QtWindow {
+    X11(std::os::raw::c_ulong),
+    Wayland {
+        surface: *mut c_void,
+        display: *mut c_void,
+    },
+}
+
+impl QtWindow {
+    /// Retrieves the handles of `window` from the platform plugin of the
+    /// application.
+    ///
+    /// # Safety
+    ///
+    /// `window` has to be a valid `QWindow` pointer, used from the GUI thread.
+    pub unsafe fn new(window: *mut c_void) -> Option<Self> {
+        const XCB: u32 = 1;
+        const WAYLAND: u32 = 2;
+
+        let platform = cpp!(unsafe [] -> u32 as "uint32_t" {
+            const QString name = QGuiApplication::platformName();
+            if (name == QLatin1String("xcb")) {
+                return 1;
+            }
+            if (name.startsWith(QLatin1String("wayland"))) {
+                return 2;
+            }
+            return 0;
+        });
+        match platform {
+            XCB => {
+                let xid = cpp!(unsafe [window as "QWindow *"] -> u64 as "uint64_t" {
+                    return window->winId();
+                });
+                Some(Self::X11(xid as std::os::raw::c_ulong))
+            }
+            WAYLAND => {
+                // The surface only exists once the window is shown.
+                let surface = cpp!(unsafe [window as "QWindow *"] -> *mut c_void as "void *" {
+                    QPlatformNativeInterface *native = QGuiApplication::platformNativeInterface();
+                    return native->nativeResourceForWindow(QByteArrayLiteral("surface"), window);
+                });
+                let display = cpp!(unsafe [] -> *mut c_void as "void *" {
+                    QPlatformNativeInterface *native = QGuiApplication::platformNativeInterface();
+                    return native->nativeResourceForIntegration(QByteArrayLiteral("wl_display"));
+                });
+                if surface.is_null() || display.is_null() {
+                    None
+                } else {
+                    Some(Self::Wayland { surface, display })
+                }
+            }
+            _ => None,
+        }
+    }
+}
@@ -61,6 +61,17 @@ impl WaylandWindowIdentifier {
         surface_ptr: *mut std::ffi::c_void,
         display_ptr: *mut std::ffi::c_void,
     ) -> Option<Self> {
+        let (conn, surface) = Self::surface_from_raw(surface_ptr, display_ptr)?;
+
+        Self::new_inner(conn, &surface).await
+    }
+
+    /// Wraps the raw pointers into a connection and a surface, which can be
+    /// moved to another thread unlike the pointers.
+    pub unsafe fn surface_from_raw(
+        surface_ptr: *mut std::ffi::c_void,
+        display_ptr: *mut std::ffi::c_void,
+    ) -> Option<(wayland_client::Connection, WlSurface)> {
         if surface_ptr.is_null() || display_ptr.is_null() {
             return None;
         }
@@ -74,11 +85,10 @@ impl WaylandWindowIdentifier {
         .ok()?;
 
         let surface = WlSurface::from_id(&conn, obj_id).ok()?;
-
-        Self::new_inner(conn, &surface).await
+        Some((conn, surface))
     }
 
-    async fn new_inner(conn: wayland_client::Connection, surface: &WlSurface) -> Option<Self> {
+    pub async fn new_inner(conn: wayland_client::Connection, surface: &WlSurface) -> Option<Self> {
         let (sender, receiver) =
             futures_channel::oneshot::channel::<Option<WaylandWindowIdentifier>>();
 